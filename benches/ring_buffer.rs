@@ -0,0 +1,52 @@
+use common::ring_buffer::{HashedRingBuffer, RingBuffer};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// history windows of a flooder, in number of remembered floods
+const SIZES: &[usize] = &[64, 1024, 16384];
+
+fn contains(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contains");
+    for &size in SIZES {
+        let mut rb: RingBuffer<(u8, u64)> = RingBuffer::with_capacity(size);
+        let mut hashed: HashedRingBuffer<(u8, u64)> = HashedRingBuffer::with_capacity(size);
+        for i in 0..size as u64 {
+            rb.insert((0, i));
+            hashed.insert((0, i));
+        }
+        // worst case for the linear scan: the flood has never been seen
+        let missing = (1, 0);
+        group.bench_with_input(BenchmarkId::new("RingBuffer", size), &missing, |b, e| {
+            b.iter(|| rb.contains(black_box(e)));
+        });
+        group.bench_with_input(BenchmarkId::new("HashedRingBuffer", size), &missing, |b, e| {
+            b.iter(|| hashed.contains(black_box(e)));
+        });
+    }
+    group.finish();
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    for &size in SIZES {
+        group.bench_function(BenchmarkId::new("RingBuffer", size), |b| {
+            let mut rb: RingBuffer<(u8, u64)> = RingBuffer::with_capacity(size);
+            let mut i = 0u64;
+            b.iter(|| {
+                i += 1;
+                rb.insert(black_box((0, i)))
+            });
+        });
+        group.bench_function(BenchmarkId::new("HashedRingBuffer", size), |b| {
+            let mut hashed: HashedRingBuffer<(u8, u64)> = HashedRingBuffer::with_capacity(size);
+            let mut i = 0u64;
+            b.iter(|| {
+                i += 1;
+                hashed.insert(black_box((0, i)))
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, contains, insert);
+criterion_main!(benches);
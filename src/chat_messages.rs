@@ -0,0 +1,252 @@
+/*! This module contains the types used to implement communication between chat clients and chat servers */
+
+#[cfg(test)]
+mod tests;
+
+use bincode::{Decode, Encode};
+
+use wg_2024::network::NodeId;
+
+/// Errors that a chat server can send back to a client
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum ChatError {
+    /// the client has to register before sending this request
+    NotRegistered,
+    /// the client identified by the given ID is not registered to the server
+    ClientNotFound(NodeId),
+    /// the server cannot understand the request
+    InvalidRequest,
+    /// there is no room with the given name
+    RoomNotFound(String),
+    /// a room with the given name already exists
+    RoomAlreadyExists(String),
+    /// the client is not a member of the room with the given name
+    NotARoomMember(String),
+}
+
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum ChatRequest {
+    Register,
+    ClientList,
+    SendMessage(NodeId, String), // client_id, text
+    /// creates a room, the creator automatically joins it
+    CreateRoom(String), // room
+    JoinRoom(String), // room
+    LeaveRoom(String), // room
+    RoomMembers(String), // room
+    /// sends a message to every other member of the room
+    SendRoomMessage(String, String), // room, text
+}
+
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum ChatResponse {
+    Registered,
+    ClientList(Vec<NodeId>),
+    MessageDelivered(NodeId), // client_id
+    IncomingMessage(NodeId, String), // client_id, text
+    Error(ChatError),
+    RoomCreated(String), // room
+    RoomJoined(String), // room
+    RoomLeft(String), // room
+    RoomMembers(String, Vec<NodeId>), // room, client_ids
+    RoomMessageSent(String), // room
+    IncomingRoomMessage(String, NodeId, String), // room, client_id, text
+    /// the recipient cannot be reached, the message will be delivered when it registers again
+    MessageQueued(NodeId), // client_id
+}
+
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct ChatRequestMessage {
+    pub source_id: NodeId,
+    pub content: ChatRequest,
+}
+
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct ChatResponseMessage {
+    pub source_id: NodeId,
+    pub content: ChatResponse,
+}
+
+impl ChatRequestMessage {
+    #[inline]
+    #[must_use]
+    pub fn new_register_request(source_id: NodeId) -> ChatRequestMessage {
+        Self {
+            source_id,
+            content: ChatRequest::Register,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_client_list_request(source_id: NodeId) -> ChatRequestMessage {
+        Self {
+            source_id,
+            content: ChatRequest::ClientList,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_send_message_request(source_id: NodeId, client_id: NodeId, text: String) -> ChatRequestMessage {
+        Self {
+            source_id,
+            content: ChatRequest::SendMessage(client_id, text),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_create_room_request(source_id: NodeId, room: String) -> ChatRequestMessage {
+        Self {
+            source_id,
+            content: ChatRequest::CreateRoom(room),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_join_room_request(source_id: NodeId, room: String) -> ChatRequestMessage {
+        Self {
+            source_id,
+            content: ChatRequest::JoinRoom(room),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_leave_room_request(source_id: NodeId, room: String) -> ChatRequestMessage {
+        Self {
+            source_id,
+            content: ChatRequest::LeaveRoom(room),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_room_members_request(source_id: NodeId, room: String) -> ChatRequestMessage {
+        Self {
+            source_id,
+            content: ChatRequest::RoomMembers(room),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_send_room_message_request(source_id: NodeId, room: String, text: String) -> ChatRequestMessage {
+        Self {
+            source_id,
+            content: ChatRequest::SendRoomMessage(room, text),
+        }
+    }
+}
+
+impl ChatResponseMessage {
+    #[inline]
+    #[must_use]
+    pub fn new_registered_response(source_id: NodeId) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::Registered,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_client_list_response(source_id: NodeId, list: Vec<NodeId>) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::ClientList(list),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_message_delivered_response(source_id: NodeId, client_id: NodeId) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::MessageDelivered(client_id),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_message_queued_response(source_id: NodeId, client_id: NodeId) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::MessageQueued(client_id),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_incoming_message_response(source_id: NodeId, client_id: NodeId, text: String) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::IncomingMessage(client_id, text),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_error_response(source_id: NodeId, error: ChatError) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::Error(error),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_room_created_response(source_id: NodeId, room: String) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::RoomCreated(room),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_room_joined_response(source_id: NodeId, room: String) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::RoomJoined(room),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_room_left_response(source_id: NodeId, room: String) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::RoomLeft(room),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_room_members_response(source_id: NodeId, room: String, list: Vec<NodeId>) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::RoomMembers(room, list),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_room_message_sent_response(source_id: NodeId, room: String) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::RoomMessageSent(room),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_incoming_room_message_response(source_id: NodeId, room: String, client_id: NodeId, text: String) -> ChatResponseMessage {
+        Self {
+            source_id,
+            content: ChatResponse::IncomingRoomMessage(room, client_id, text),
+        }
+    }
+}
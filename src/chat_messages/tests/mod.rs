@@ -0,0 +1,69 @@
+use bincode::config::standard;
+use bincode::Encode;
+
+use crate::chat_messages::{ChatError, ChatRequest, ChatRequestMessage, ChatResponse, ChatResponseMessage};
+use crate::web_messages::Serializable;
+
+#[test]
+fn chat_request_round_trip() {
+    let requests = [
+        ChatRequestMessage::new_register_request(1),
+        ChatRequestMessage::new_client_list_request(1),
+        ChatRequestMessage::new_send_message_request(1, 2, "hi".to_string()),
+        ChatRequestMessage::new_create_room_request(1, "rust".to_string()),
+        ChatRequestMessage::new_join_room_request(1, "rust".to_string()),
+        ChatRequestMessage::new_leave_room_request(1, "rust".to_string()),
+        ChatRequestMessage::new_room_members_request(1, "rust".to_string()),
+        ChatRequestMessage::new_send_room_message_request(1, "rust".to_string(), "hello room".to_string()),
+    ];
+    for req in requests {
+        let data = req.serialize().unwrap();
+        assert_eq!(<ChatRequestMessage as Serializable>::deserialize(data).unwrap(), req);
+    }
+}
+
+#[test]
+fn chat_response_round_trip() {
+    let responses = [
+        ChatResponseMessage::new_registered_response(9),
+        ChatResponseMessage::new_client_list_response(9, vec![1, 2]),
+        ChatResponseMessage::new_message_delivered_response(9, 2),
+        ChatResponseMessage::new_message_queued_response(9, 2),
+        ChatResponseMessage::new_incoming_message_response(9, 1, "hi".to_string()),
+        ChatResponseMessage::new_error_response(9, ChatError::RoomNotFound("go".to_string())),
+        ChatResponseMessage::new_room_created_response(9, "rust".to_string()),
+        ChatResponseMessage::new_room_joined_response(9, "rust".to_string()),
+        ChatResponseMessage::new_room_left_response(9, "rust".to_string()),
+        ChatResponseMessage::new_room_members_response(9, "rust".to_string(), vec![1, 2]),
+        ChatResponseMessage::new_room_message_sent_response(9, "rust".to_string()),
+        ChatResponseMessage::new_incoming_room_message_response(9, "rust".to_string(), 1, "hello room".to_string()),
+    ];
+    for resp in responses {
+        let data = resp.serialize().unwrap();
+        assert_eq!(<ChatResponseMessage as Serializable>::deserialize(data).unwrap(), resp);
+    }
+}
+
+fn variant<T: Encode>(value: T) -> u8 {
+    bincode::encode_to_vec(value, standard()).unwrap()[0]
+}
+
+#[test]
+fn chat_variant_numbers() {
+    // variants added after the first release must not renumber the existing ones
+    assert_eq!(variant(ChatRequest::Register), 0);
+    assert_eq!(variant(ChatRequest::ClientList), 1);
+    assert_eq!(variant(ChatRequest::SendMessage(1, String::new())), 2);
+
+    assert_eq!(variant(ChatResponse::Registered), 0);
+    assert_eq!(variant(ChatResponse::ClientList(vec![])), 1);
+    assert_eq!(variant(ChatResponse::MessageDelivered(1)), 2);
+    assert_eq!(variant(ChatResponse::IncomingMessage(1, String::new())), 3);
+    assert_eq!(variant(ChatResponse::Error(ChatError::NotRegistered)), 4);
+    assert_eq!(variant(ChatResponse::RoomCreated(String::new())), 5);
+    assert_eq!(variant(ChatResponse::IncomingRoomMessage(String::new(), 1, String::new())), 10);
+
+    assert_eq!(variant(ChatError::NotRegistered), 0);
+    assert_eq!(variant(ChatError::ClientNotFound(1)), 1);
+    assert_eq!(variant(ChatError::InvalidRequest), 2);
+}
@@ -0,0 +1,120 @@
+/*!
+    This module contains the checksum used to verify files transferred between web clients and servers
+*/
+
+use std::fmt;
+
+/// lookup table of the reflected CRC-32 (IEEE 802.3) polynomial
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        // i < 256 so the cast is lossless
+        #[allow(clippy::cast_possible_truncation)]
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// computes the CRC-32 (IEEE 802.3, same as zlib) of the data
+/// * data: bytes to be checked
+#[must_use]
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &b| {
+        CRC32_TABLE[((crc ^ u32::from(b)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Error generated when a file cannot be verified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityError {
+    /// the response does not carry a file
+    NotAFile,
+    /// the response carries a file but no digest
+    MissingDigest,
+    /// the file does not match its digest
+    DigestMismatch { expected: u32, actual: u32 },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::NotAFile => write!(f, "Response does not carry a file"),
+            IntegrityError::MissingDigest => write!(f, "Missing digest"),
+            IntegrityError::DigestMismatch { expected, actual } => {
+                write!(f, "Digest mismatch: expected {expected:#010x}, got {actual:#010x}")
+            }
+        }
+    }
+}
+impl std::error::Error for IntegrityError {}
+
+/// checks that the data matches the expected crc32
+/// * data: bytes to be checked
+/// * expected: crc32 sent along with the data
+/// # Errors
+///
+/// Will return Err if the crc32 of the data is different from the expected one
+pub fn verify(data: &[u8], expected: u32) -> Result<(), IntegrityError> {
+    let actual = crc32(data);
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(IntegrityError::DigestMismatch { expected, actual })
+    }
+}
+
+/// File whose content has been checked against its digest
+///
+/// the only way to build a `TextMediaResponse`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedFile {
+    name: String,
+    data: Vec<u8>,
+}
+
+impl VerifiedFile {
+    /// Constructor that verifies the content of the file
+    /// * name: filename
+    /// * data: file content
+    /// * digest: crc32 sent along with the file
+    /// # Errors
+    ///
+    /// Will return Err if the content does not match the digest
+    pub fn new(name: String, data: Vec<u8>, digest: u32) -> Result<Self, IntegrityError> {
+        verify(&data, digest)?;
+        Ok(Self { name, data })
+    }
+
+    /// Getter of the filename
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Getter of the file content
+    #[inline]
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// returns the pair (filename, file content)
+    #[inline]
+    #[must_use]
+    pub fn into_parts(self) -> (String, Vec<u8>) {
+        (self.name, self.data)
+    }
+}
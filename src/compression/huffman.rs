@@ -0,0 +1,187 @@
+/*!
+    Canonical Huffman codec used by `Compression::Huffman`
+
+    Stream layout:
+    * original length: u64 little endian
+    * number of coded symbols minus one: u8
+    * for each coded symbol: (symbol: u8, code length: u8), sorted by (length, symbol)
+    * codes packed MSB first, last byte padded with zeros
+
+    Codes are assigned canonically from the lengths, so the table is all a decoder needs.
+    An empty payload is encoded as an empty stream.
+*/
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::{BitReader, BitWriter, CompressionError};
+
+/// longest code the codec accepts (a code this long needs a payload of several terabytes)
+///
+/// kept below 64 so that shifting a code by its length never overflows a u64
+const MAX_CODE_LEN: u8 = 63;
+
+/// computes the code length of every byte value from the frequencies (0 for unused values)
+fn code_lengths(freqs: &[u64; 256]) -> [u8; 256] {
+    let mut lengths = [0; 256];
+    let mut symbols: Vec<usize> = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for (symbol, &freq) in freqs.iter().enumerate() {
+        if freq > 0 {
+            heap.push(Reverse((freq, symbols.len())));
+            symbols.push(symbol);
+        }
+    }
+    let leaves = symbols.len();
+
+    if leaves == 1 {
+        lengths[symbols[0]] = 1;
+        return lengths;
+    }
+
+    // leaves take the first indices of `nodes`, internal nodes are appended while merging
+    let mut nodes: Vec<Option<usize>> = vec![None; leaves];
+    while let (Some(Reverse((f1, n1))), Some(Reverse((f2, n2)))) = (heap.pop(), heap.pop()) {
+        let parent = nodes.len();
+        nodes.push(None);
+        nodes[n1] = Some(parent);
+        nodes[n2] = Some(parent);
+        heap.push(Reverse((f1 + f2, parent)));
+    }
+
+    for (leaf, &symbol) in symbols.iter().enumerate() {
+        let mut depth = 0u8;
+        let mut node = leaf;
+        while let Some(parent) = nodes[node] {
+            depth += 1;
+            node = parent;
+        }
+        lengths[symbol] = depth;
+    }
+    lengths
+}
+
+/// sorts the coded symbols canonically and assigns them their codes
+///
+/// the lengths must satisfy the Kraft inequality, codes are then guaranteed to fit in their length
+/// * lengths: pairs (symbol, code length)
+fn canonical_codes(lengths: &mut [(u8, u8)]) -> Vec<u64> {
+    lengths.sort_unstable_by_key(|&(symbol, len)| (len, symbol));
+    let mut codes = Vec::with_capacity(lengths.len());
+    let mut code = 0u64;
+    let mut prev_len = 0;
+    for &(_, len) in lengths.iter() {
+        code <<= len - prev_len;
+        codes.push(code);
+        code += 1;
+        prev_len = len;
+    }
+    codes
+}
+
+/// compresses the payload
+/// * data: payload to be compressed
+#[must_use]
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let mut freqs = [0u64; 256];
+    for &b in data {
+        freqs[b as usize] += 1;
+    }
+    let mut table: Vec<(u8, u8)> = (0..=u8::MAX)
+        .zip(code_lengths(&freqs))
+        .filter(|&(_, len)| len > 0)
+        .collect();
+    let codes = canonical_codes(&mut table);
+
+    let mut lookup = [(0u64, 0u8); 256];
+    for (&(symbol, len), &code) in table.iter().zip(&codes) {
+        lookup[symbol as usize] = (code, len);
+    }
+
+    let mut out = Vec::with_capacity(9 + 2 * table.len() + data.len() / 2);
+    out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    // there is at least one symbol and at most 256
+    #[allow(clippy::cast_possible_truncation)]
+    out.push((table.len() - 1) as u8);
+    for &(symbol, len) in &table {
+        out.push(symbol);
+        out.push(len);
+    }
+
+    let mut writer = BitWriter::new();
+    for &b in data {
+        let (code, len) = lookup[b as usize];
+        writer.write(code, u32::from(len));
+    }
+    out.extend(writer.finish());
+    out
+}
+
+/// decompresses a payload produced by `compress`
+/// * data: compressed payload
+/// # Errors
+///
+/// Will return Err if the code table is malformed or the stream ends before all symbols are decoded
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (len_bytes, rest) = data.split_first_chunk::<8>().ok_or(CompressionError)?;
+    let original_len = usize::try_from(u64::from_le_bytes(*len_bytes)).map_err(|_| CompressionError)?;
+    let (&n_symbols, rest) = rest.split_first().ok_or(CompressionError)?;
+    let table_len = (usize::from(n_symbols) + 1) * 2;
+    if rest.len() < table_len {
+        return Err(CompressionError);
+    }
+    let (table_bytes, stream) = rest.split_at(table_len);
+
+    let mut table: Vec<(u8, u8)> = table_bytes.chunks_exact(2).map(|c| (c[0], c[1])).collect();
+    if table.iter().any(|&(_, len)| len == 0 || len > MAX_CODE_LEN) {
+        return Err(CompressionError);
+    }
+    let kraft: u128 = table.iter().map(|&(_, len)| 1u128 << (MAX_CODE_LEN - len)).sum();
+    if kraft > 1u128 << MAX_CODE_LEN {
+        return Err(CompressionError);
+    }
+    let codes = canonical_codes(&mut table);
+
+    // first code and index in `table` of the first symbol of every length
+    let mut first: Vec<Option<(u64, usize)>> = vec![None; usize::from(MAX_CODE_LEN) + 1];
+    let mut count = vec![0usize; usize::from(MAX_CODE_LEN) + 1];
+    for (i, (&(_, len), &code)) in table.iter().zip(&codes).enumerate() {
+        let len = usize::from(len);
+        first[len].get_or_insert((code, i));
+        count[len] += 1;
+    }
+
+    let mut reader = BitReader::new(stream);
+    // every decoded symbol consumes at least one bit
+    let mut out = Vec::with_capacity(original_len.min(reader.remaining()));
+    while out.len() < original_len {
+        let mut code = 0u64;
+        let mut len = 0;
+        let symbol = loop {
+            code = (code << 1) | u64::from(reader.read_bit().ok_or(CompressionError)?);
+            len += 1;
+            if len > usize::from(MAX_CODE_LEN) {
+                return Err(CompressionError);
+            }
+            if let Some((first_code, index)) = first[len] {
+                let offset = code.wrapping_sub(first_code);
+                if offset < count[len] as u64 {
+                    // offset < count[len] <= 256
+                    #[allow(clippy::cast_possible_truncation)]
+                    break table[index + offset as usize].0;
+                }
+            }
+        };
+        out.push(symbol);
+    }
+    Ok(out)
+}
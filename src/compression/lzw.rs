@@ -0,0 +1,102 @@
+/*!
+    LZW codec used by `Compression::LZW`
+
+    The stream is a sequence of dictionary codes packed MSB first.
+    The k-th code is written with just enough bits to represent every entry the dictionary
+    holds at that point, so encoder and decoder agree on the width without any header.
+    The dictionary stops growing at `MAX_ENTRIES` entries and is never reset.
+*/
+
+use std::collections::HashMap;
+
+use super::{BitReader, BitWriter, CompressionError};
+
+/// maximum number of entries in the dictionary (codes are at most 16 bits wide)
+const MAX_ENTRIES: usize = 1 << 16;
+
+/// number of bits used to write the k-th code of the stream
+fn code_width(k: usize) -> u32 {
+    let entries = (0x100 + k).min(MAX_ENTRIES);
+    usize::BITS - (entries - 1).leading_zeros()
+}
+
+/// compresses the payload
+/// * data: payload to be compressed
+#[must_use]
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut dict: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = 0x100;
+    let mut writer = BitWriter::new();
+    let mut emitted = 0;
+
+    let Some((&first, rest)) = data.split_first() else {
+        return Vec::new();
+    };
+    let mut current = u16::from(first);
+    for &byte in rest {
+        if let Some(&code) = dict.get(&(current, byte)) {
+            current = code;
+            continue;
+        }
+
+        writer.write(u64::from(current), code_width(emitted));
+        emitted += 1;
+        if next_code < MAX_ENTRIES {
+            // next_code < 2^16 so the cast is lossless
+            #[allow(clippy::cast_possible_truncation)]
+            dict.insert((current, byte), next_code as u16);
+            next_code += 1;
+        }
+        current = u16::from(byte);
+    }
+    writer.write(u64::from(current), code_width(emitted));
+
+    writer.finish()
+}
+
+/// decompresses a payload produced by `compress`
+///
+/// every entry of the dictionary is stored as (prefix code, last byte) and expanded on output,
+/// so memory grows with the number of entries and not with their length
+/// * data: compressed payload
+/// # Errors
+///
+/// Will return Err if a code in the stream refers to an entry that does not exist
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    // (prefix code, last byte, first byte) of every entry
+    let mut dict: Vec<(Option<usize>, u8, u8)> = (0..=u8::MAX).map(|b| (None, b, b)).collect();
+    let mut reader = BitReader::new(data);
+    let mut out = Vec::new();
+    let mut prev: Option<usize> = None;
+    let mut k = 0;
+
+    // padding is always shorter than a byte and every code is at least 8 bits wide
+    while let Some(code) = reader.read(code_width(k)) {
+        let code = usize::try_from(code).map_err(|_| CompressionError)?;
+        let first = match (prev, dict.get(code)) {
+            (_, Some(&(_, _, first))) => first,
+            // the entry being defined by this very code: previous entry + its first byte
+            (Some(p), None) if code == dict.len() => dict[p].2,
+            _ => return Err(CompressionError),
+        };
+
+        if let Some(p) = prev {
+            if dict.len() < MAX_ENTRIES {
+                dict.push((Some(p), first, dict[p].2));
+            }
+        }
+
+        let start = out.len();
+        let mut current = Some(dict.get(code).ok_or(CompressionError)?);
+        while let Some(&(prefix, last, _)) = current {
+            out.push(last);
+            current = prefix.map(|p| &dict[p]);
+        }
+        out[start..].reverse();
+
+        prev = Some(code);
+        k += 1;
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,141 @@
+/*!
+    This module contains the codecs behind the `Compression` variants used in web client-server communication
+*/
+
+#[cfg(test)]
+mod tests;
+
+pub mod huffman;
+pub mod lzw;
+
+use std::fmt;
+
+use crate::web_messages::Compression;
+
+/// Error generated when a compressed payload cannot be decoded
+#[derive(Debug)]
+pub struct CompressionError;
+impl fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Compression Error")
+    }
+}
+impl std::error::Error for CompressionError {}
+
+/// compressions implemented by this crate, from the most to the least preferred
+pub const SUPPORTED_COMPRESSIONS: &[Compression] = &[Compression::LZW, Compression::Huffman, Compression::None];
+
+impl Compression {
+    /// picks the most preferred compression supported by both sides
+    ///
+    /// falls back to `Compression::None` when the sides share no codec
+    /// * local: compressions supported by this node
+    /// * remote: compressions supported by the other node (e.g. from `GenericResponse::Capabilities`)
+    #[must_use]
+    pub fn negotiate(local: &[Compression], remote: &[Compression]) -> Compression {
+        SUPPORTED_COMPRESSIONS
+            .iter()
+            .find(|c| local.contains(c) && remote.contains(c))
+            .cloned()
+            .unwrap_or(Compression::None)
+    }
+
+    /// compresses the payload with the codec identified by `self`
+    /// * data: payload to be compressed
+    #[must_use]
+    pub fn compress(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Compression::None => data.to_vec(),
+            Compression::LZW => lzw::compress(data),
+            Compression::Huffman => huffman::compress(data),
+        }
+    }
+
+    /// decompresses a payload that was compressed with the codec identified by `self`
+    /// * data: payload to be decompressed
+    /// # Errors
+    ///
+    /// Will return Err if data is not a valid stream for the codec
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        match self {
+            Compression::None => Ok(data.to_vec()),
+            Compression::LZW => lzw::decompress(data),
+            Compression::Huffman => huffman::decompress(data),
+        }
+    }
+}
+
+/// Writes values into a byte vector one bit at a time, most significant bit first
+pub(crate) struct BitWriter {
+    out: Vec<u8>,
+    current: u8,
+    used: u32,
+}
+
+impl BitWriter {
+    pub(crate) fn new() -> Self {
+        Self {
+            out: Vec::new(),
+            current: 0,
+            used: 0,
+        }
+    }
+
+    /// appends the `width` least significant bits of `value`
+    pub(crate) fn write(&mut self, value: u64, width: u32) {
+        for i in (0..width).rev() {
+            self.current = (self.current << 1) | u8::from((value >> i) & 1 == 1);
+            self.used += 1;
+            if self.used == 8 {
+                self.out.push(self.current);
+                self.current = 0;
+                self.used = 0;
+            }
+        }
+    }
+
+    /// pads the last byte with zeros and returns the written bytes
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        if self.used > 0 {
+            self.out.push(self.current << (8 - self.used));
+        }
+        self.out
+    }
+}
+
+/// Reads values from a byte slice one bit at a time, most significant bit first
+pub(crate) struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// number of bits that have not been read yet
+    pub(crate) fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.pos
+    }
+
+    /// reads the next bit, returns None at the end of the data
+    pub(crate) fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.data.get(self.pos / 8)?;
+        let bit = (byte >> (7 - self.pos % 8)) & 1 == 1;
+        self.pos += 1;
+        Some(bit)
+    }
+
+    /// reads the next `width` bits as an unsigned value, returns None if not enough bits are left
+    pub(crate) fn read(&mut self, width: u32) -> Option<u64> {
+        if self.remaining() < width as usize {
+            return None;
+        }
+        let mut value = 0;
+        for _ in 0..width {
+            value = (value << 1) | u64::from(self.read_bit()?);
+        }
+        Some(value)
+    }
+}
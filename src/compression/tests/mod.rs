@@ -0,0 +1,165 @@
+use proptest::prelude::*;
+
+use crate::compression::{huffman, lzw};
+use crate::web_messages::{Compression, ResponseMessage, Serializable};
+
+const HTML: &str = "<html><head><title>Test</title></head><body>\
+    <p>TOBEORNOTTOBEORTOBEORNOT</p><img src=\"image.png\"><img src=\"image.jpg\">\
+    <p>TOBEORNOTTOBEORTOBEORNOT</p></body></html>";
+
+/// deterministic pseudo-random bytes that look like binary media
+fn binary_media(len: usize) -> Vec<u8> {
+    let mut state: u32 = 0x1234_5678;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state.to_le_bytes()[0]
+        })
+        .collect()
+}
+
+#[test]
+fn lzw_empty() {
+    assert!(lzw::compress(&[]).is_empty());
+    assert!(lzw::decompress(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn lzw_single_byte() {
+    assert_eq!(lzw::decompress(&lzw::compress(&[42])).unwrap(), vec![42]);
+}
+
+#[test]
+fn lzw_text() {
+    let compressed = lzw::compress(HTML.as_bytes());
+    assert!(compressed.len() < HTML.len());
+    assert_eq!(lzw::decompress(&compressed).unwrap(), HTML.as_bytes());
+}
+
+#[test]
+fn lzw_repeated_pattern() {
+    // exercises the code that is used before the decoder has added it to the dictionary
+    let data = vec![b'a'; 10_000];
+    let compressed = lzw::compress(&data);
+    assert!(compressed.len() < 300);
+    assert_eq!(lzw::decompress(&compressed).unwrap(), data);
+}
+
+#[test]
+fn lzw_binary_media() {
+    // large enough to fill the dictionary
+    let data = binary_media(200_000);
+    assert_eq!(lzw::decompress(&lzw::compress(&data)).unwrap(), data);
+}
+
+#[test]
+fn lzw_invalid_code() {
+    // first code is 8 bits wide, second one is 9 bits wide and refers to a missing entry
+    assert!(lzw::decompress(&[0x41, 0xff, 0x80]).is_err());
+}
+
+#[test]
+fn response_message_lzw() {
+    let msg = ResponseMessage::new_text_response(1, Compression::LZW, HTML.as_bytes().to_vec());
+    let plain = ResponseMessage::new_text_response(1, Compression::None, HTML.as_bytes().to_vec());
+
+    let data = msg.serialize().unwrap();
+    assert!(data.len() < plain.serialize().unwrap().len());
+    assert_eq!(<ResponseMessage as Serializable>::deserialize(data).unwrap(), msg);
+
+    let media = ResponseMessage::new_media_response(1, Compression::LZW, binary_media(5000));
+    let data = media.serialize().unwrap();
+    assert_eq!(<ResponseMessage as Serializable>::deserialize(data).unwrap(), media);
+}
+
+#[test]
+fn negotiate() {
+    use Compression::{Huffman, None, LZW};
+
+    assert_eq!(Compression::negotiate(&[LZW, Huffman, None], &[None, Huffman, LZW]), LZW);
+    assert_eq!(Compression::negotiate(&[LZW, Huffman], &[Huffman]), Huffman);
+    assert_eq!(Compression::negotiate(&[LZW], &[Huffman]), None);
+    assert_eq!(Compression::negotiate(&[], &[]), None);
+}
+
+#[test]
+fn huffman_empty() {
+    assert!(huffman::compress(&[]).is_empty());
+    assert!(huffman::decompress(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn huffman_single_symbol() {
+    let data = vec![7; 1000];
+    let compressed = huffman::compress(&data);
+    assert!(compressed.len() < 150);
+    assert_eq!(huffman::decompress(&compressed).unwrap(), data);
+}
+
+#[test]
+fn huffman_text() {
+    let text = HTML.repeat(20);
+    let compressed = huffman::compress(text.as_bytes());
+    assert!(compressed.len() < text.len());
+    assert_eq!(huffman::decompress(&compressed).unwrap(), text.as_bytes());
+}
+
+#[test]
+fn huffman_binary_media() {
+    let data = binary_media(200_000);
+    assert_eq!(huffman::decompress(&huffman::compress(&data)).unwrap(), data);
+}
+
+#[test]
+fn huffman_truncated_stream() {
+    let compressed = huffman::compress(HTML.as_bytes());
+    assert!(huffman::decompress(&compressed[..compressed.len() - 4]).is_err());
+    assert!(huffman::decompress(&compressed[..5]).is_err());
+}
+
+#[test]
+fn huffman_invalid_table() {
+    // three symbols with a 1 bit code
+    let mut data = 3u64.to_le_bytes().to_vec();
+    data.extend([2, b'a', 1, b'b', 1, b'c', 1, 0]);
+    assert!(huffman::decompress(&data).is_err());
+}
+
+#[test]
+fn huffman_code_too_long() {
+    // a single symbol with a 64 bits code
+    let mut data = 1u64.to_le_bytes().to_vec();
+    data.extend([0, b'a', 64]);
+    data.extend([0; 8]);
+    assert!(huffman::decompress(&data).is_err());
+
+    // the longest accepted code
+    data[10] = 63;
+    assert_eq!(huffman::decompress(&data).unwrap(), b"a");
+}
+
+#[test]
+fn response_message_huffman() {
+    let msg = ResponseMessage::new_text_response(1, Compression::Huffman, HTML.as_bytes().to_vec());
+    let data = msg.serialize().unwrap();
+    assert_eq!(<ResponseMessage as Serializable>::deserialize(data).unwrap(), msg);
+}
+
+proptest! {
+    #[test]
+    fn lzw_roundtrip(data in proptest::collection::vec(any::<u8>(), 0..4096)) {
+        prop_assert_eq!(lzw::decompress(&lzw::compress(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn huffman_roundtrip(data in proptest::collection::vec(any::<u8>(), 0..4096)) {
+        prop_assert_eq!(huffman::decompress(&huffman::compress(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn huffman_roundtrip_skewed(data in proptest::collection::vec(prop_oneof![8 => Just(0u8), 2 => 1u8..4, 1 => any::<u8>()], 0..4096)) {
+        prop_assert_eq!(huffman::decompress(&huffman::compress(&data)).unwrap(), data);
+    }
+}
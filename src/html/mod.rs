@@ -0,0 +1,190 @@
+/*!
+    This module contains the helpers used by web clients to find the media linked by a text file
+    and to assemble the `TextMediaResponse` sent to scl
+*/
+
+#[cfg(test)]
+mod tests;
+
+use crate::checksum::VerifiedFile;
+use crate::slc_commands::TextMediaResponse;
+
+/// tags whose attributes may reference a media file
+const MEDIA_TAGS: &[&[u8]] = &[b"img", b"video", b"audio", b"source", b"embed"];
+/// attributes that reference a media file
+const MEDIA_ATTRIBUTES: &[&[u8]] = &[b"src", b"poster"];
+
+/// extracts the media filenames referenced by an html file
+///
+/// looks at the `src` and `poster` attributes of `img`, `video`, `audio`, `source` and `embed` tags,
+/// ignoring comments, inline `data:` media and external URLs.
+/// Every filename is returned once, in order of appearance
+/// * html: content of the text file
+#[must_use]
+pub fn extract_media_links(html: &[u8]) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = html[pos..].iter().position(|&b| b == b'<') {
+        pos += offset + 1;
+        if html[pos..].starts_with(b"!--") {
+            pos = find(html, pos + 3, b"-->").map_or(html.len(), |end| end + 3);
+            continue;
+        }
+        let name_end = pos + html[pos..].iter().take_while(|b| b.is_ascii_alphanumeric()).count();
+        let is_media = MEDIA_TAGS.iter().any(|tag| html[pos..name_end].eq_ignore_ascii_case(tag));
+        pos = name_end;
+        for (name, value) in Attributes::new(html, &mut pos) {
+            if !is_media || !MEDIA_ATTRIBUTES.iter().any(|attr| name.eq_ignore_ascii_case(attr)) {
+                continue;
+            }
+            let value = String::from_utf8_lossy(value).trim().to_string();
+            if is_local(&value) && !links.contains(&value) {
+                links.push(value);
+            }
+        }
+    }
+    links
+}
+
+/// returns the position of the first occurrence of needle in haystack after start
+fn find(haystack: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+    haystack
+        .get(start..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|i| start + i)
+}
+
+/// checks that a reference can be requested to a media server
+fn is_local(value: &str) -> bool {
+    !value.is_empty() && !value.contains("://") && !value.to_ascii_lowercase().starts_with("data:")
+}
+
+/// Iterator over the (name, value) attributes of a tag, leaving the position after its end
+struct Attributes<'a, 'b> {
+    html: &'a [u8],
+    pos: &'b mut usize,
+}
+
+impl<'a, 'b> Attributes<'a, 'b> {
+    fn new(html: &'a [u8], pos: &'b mut usize) -> Self {
+        Self { html, pos }
+    }
+
+    fn skip_while(&mut self, f: impl Fn(u8) -> bool) -> &'a [u8] {
+        let start = *self.pos;
+        while *self.pos < self.html.len() && f(self.html[*self.pos]) {
+            *self.pos += 1;
+        }
+        &self.html[start..*self.pos]
+    }
+}
+
+impl<'a> Iterator for Attributes<'a, '_> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.skip_while(|b| b.is_ascii_whitespace() || b == b'/');
+            match self.html.get(*self.pos) {
+                None => return None,
+                Some(b'>') => {
+                    *self.pos += 1;
+                    return None;
+                }
+                Some(_) => {}
+            }
+            let name = self.skip_while(|b| !b.is_ascii_whitespace() && !matches!(b, b'=' | b'>' | b'/'));
+            if name.is_empty() {
+                // stray character, e.g. a lone '=' or a quote
+                *self.pos += 1;
+                continue;
+            }
+            self.skip_while(|b| b.is_ascii_whitespace());
+            if self.html.get(*self.pos) != Some(&b'=') {
+                return Some((name, &[]));
+            }
+            *self.pos += 1;
+            self.skip_while(|b| b.is_ascii_whitespace());
+            let value = match self.html.get(*self.pos) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    *self.pos += 1;
+                    let value = self.skip_while(|b| b != quote);
+                    *self.pos = (*self.pos + 1).min(self.html.len());
+                    value
+                }
+                _ => self.skip_while(|b| !b.is_ascii_whitespace() && b != b'>'),
+            };
+            return Some((name, value));
+        }
+    }
+}
+
+/// Collects the media linked by a text file until the `TextMediaResponse` can be built
+#[derive(Debug, Clone)]
+pub struct TextMediaBuilder {
+    html_file: VerifiedFile,
+    links: Vec<String>,
+    media_files: Vec<Option<VerifiedFile>>,
+}
+
+impl TextMediaBuilder {
+    /// constructor of the builder, extracting the media linked by the text file
+    /// * `html_file`: verified text file
+    #[must_use]
+    pub fn new(html_file: VerifiedFile) -> Self {
+        let links = extract_media_links(html_file.data());
+        let media_files = vec![None; links.len()];
+        Self {
+            html_file,
+            links,
+            media_files,
+        }
+    }
+
+    /// Getter of the media filenames linked by the text file
+    #[inline]
+    #[must_use]
+    pub fn links(&self) -> &[String] {
+        &self.links
+    }
+
+    /// returns the media filenames that have not been added yet, to be requested
+    #[must_use]
+    pub fn missing(&self) -> Vec<&str> {
+        self.links
+            .iter()
+            .zip(&self.media_files)
+            .filter(|(_, file)| file.is_none())
+            .map(|(link, _)| link.as_str())
+            .collect()
+    }
+
+    /// checks whether every linked media has been added
+    #[inline]
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.media_files.iter().all(Option::is_some)
+    }
+
+    /// adds a media file, returns false if the text file does not link it
+    /// * file: verified media file
+    pub fn add_media(&mut self, file: VerifiedFile) -> bool {
+        match self.links.iter().position(|link| link == file.name()) {
+            Some(i) => {
+                self.media_files[i] = Some(file);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// builds the `TextMediaResponse`, media are sorted as they appear in the text file
+    ///
+    /// returns None if some media are still missing
+    #[must_use]
+    pub fn build(self) -> Option<TextMediaResponse> {
+        let media_files = self.media_files.into_iter().collect::<Option<Vec<_>>>()?;
+        Some(TextMediaResponse::new(self.html_file, media_files))
+    }
+}
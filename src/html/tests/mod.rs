@@ -0,0 +1,55 @@
+use crate::checksum::{crc32, VerifiedFile};
+use crate::html::{extract_media_links, TextMediaBuilder};
+
+fn verified(name: &str, data: &[u8]) -> VerifiedFile {
+    VerifiedFile::new(name.to_string(), data.to_vec(), crc32(data)).unwrap()
+}
+
+#[test]
+fn extract_links() {
+    let html = br#"<html><body>
+        <h1 class="title">Gallery</h1>
+        <IMG SRC="cat.png" alt="a cat">
+        <img alt='dog' src='dog.jpg'/>
+        <img src=bird.gif>
+        <!-- <img src="hidden.png"> -->
+        <video poster="poster.png" controls><source src="clip.mp4" type="video/mp4"></video>
+        <audio src = "song.mp3"></audio>
+        <a href="other.html">link</a>
+        <img src="cat.png">
+        <img src="https://example.com/remote.png">
+        <img src="data:image/png;base64,AAAA">
+        <script src="app.js"></script>
+    </body></html>"#;
+    assert_eq!(
+        extract_media_links(html),
+        vec!["cat.png", "dog.jpg", "bird.gif", "poster.png", "clip.mp4", "song.mp3"]
+    );
+    assert!(extract_media_links(b"<p>no media</p>").is_empty());
+    assert!(extract_media_links(b"<img src=\"unterminated").len() == 1);
+}
+
+#[test]
+fn builder() {
+    let html = verified("index.html", b"<img src=\"a.png\"><img src=\"b.png\">");
+    let mut builder = TextMediaBuilder::new(html.clone());
+    assert_eq!(builder.missing(), vec!["a.png", "b.png"]);
+    assert!(!builder.add_media(verified("c.png", b"c")));
+    assert!(builder.add_media(verified("b.png", b"b")));
+    assert_eq!(builder.missing(), vec!["a.png"]);
+    assert!(!builder.is_complete());
+    assert!(builder.clone().build().is_none());
+
+    assert!(builder.add_media(verified("a.png", b"a")));
+    assert!(builder.is_complete());
+    let response = builder.build().unwrap();
+    assert_eq!(response.get_html_file(), &html.into_parts());
+    assert_eq!(
+        response.get_media_files(),
+        &vec![("a.png".to_string(), b"a".to_vec()), ("b.png".to_string(), b"b".to_vec())]
+    );
+
+    let text = TextMediaBuilder::new(verified("plain.html", b"<p>text</p>"));
+    assert!(text.is_complete());
+    assert!(text.build().unwrap().get_media_files().is_empty());
+}
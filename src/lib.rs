@@ -10,8 +10,16 @@ use std::collections::HashMap;
 use wg_2024::network::NodeId;
 use wg_2024::packet::Packet;
 
+pub mod chat_messages;
+pub mod checksum;
+pub mod compression;
+pub mod html;
+pub mod mailbox;
+pub mod media_resolver;
+pub mod media_transfer;
 pub mod networking;
 pub mod ring_buffer;
+pub mod server_discovery;
 pub mod slc_commands;
 pub mod web_messages;
 
@@ -0,0 +1,65 @@
+/*!
+    This module contains the mailbox used by chat servers to keep messages for clients that cannot be reached
+*/
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use wg_2024::network::NodeId;
+
+use crate::chat_messages::ChatResponse;
+use crate::ring_buffer::RingBuffer;
+
+/// Bounded queue of undelivered responses for every client of a chat server
+///
+/// when a queue is full the oldest message is dropped to make room for the new one
+#[derive(Debug)]
+pub struct Mailbox {
+    capacity: usize,
+    queues: HashMap<NodeId, RingBuffer<ChatResponse>>,
+}
+
+impl Mailbox {
+    /// constructor of an empty mailbox
+    /// * capacity: maximum number of messages queued for a single client
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            queues: HashMap::new(),
+        }
+    }
+
+    /// queues a message (e.g. `ChatResponse::IncomingMessage`) for a client that cannot be reached
+    ///
+    /// returns the oldest message of the client if it had to be dropped
+    /// * `client_id`: ID of the recipient
+    /// * message: response to deliver when the client registers again
+    pub fn store(&mut self, client_id: NodeId, message: ChatResponse) -> Option<ChatResponse> {
+        self.queues
+            .entry(client_id)
+            .or_insert_with(|| RingBuffer::with_capacity(self.capacity))
+            .insert(message)
+    }
+
+    /// removes the messages queued for a client, oldest first
+    ///
+    /// to be called when the client registers again
+    /// * `client_id`: ID of the client
+    pub fn take(&mut self, client_id: NodeId) -> Vec<ChatResponse> {
+        self.queues
+            .remove(&client_id)
+            .map(|queue| queue.into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// checks if there are messages queued for a client
+    /// * `client_id`: ID of the client
+    #[must_use]
+    pub fn has_messages(&self, client_id: NodeId) -> bool {
+        self.queues.get(&client_id).is_some_and(|q| !q.is_empty())
+    }
+}
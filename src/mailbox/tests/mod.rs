@@ -0,0 +1,52 @@
+use crate::chat_messages::{ChatResponse, ChatResponseMessage};
+use crate::mailbox::Mailbox;
+
+fn incoming(text: &str) -> ChatResponse {
+    ChatResponse::IncomingMessage(1, text.to_string())
+}
+
+#[test]
+fn store_and_take() {
+    let mut mailbox = Mailbox::with_capacity(4);
+    assert_eq!(mailbox.store(2, incoming("a")), None);
+    assert_eq!(mailbox.store(2, incoming("b")), None);
+    assert_eq!(mailbox.store(3, incoming("c")), None);
+
+    assert_eq!(mailbox.take(2), vec![incoming("a"), incoming("b")]);
+    assert_eq!(mailbox.take(3), vec![incoming("c")]);
+}
+
+#[test]
+fn full_queue_drops_oldest() {
+    let mut mailbox = Mailbox::with_capacity(2);
+    assert_eq!(mailbox.store(2, incoming("a")), None);
+    assert_eq!(mailbox.store(2, incoming("b")), None);
+    assert_eq!(mailbox.store(2, incoming("c")), Some(incoming("a")));
+    assert_eq!(mailbox.take(2), vec![incoming("b"), incoming("c")]);
+}
+
+#[test]
+fn take_unknown_client() {
+    let mut mailbox = Mailbox::with_capacity(2);
+    assert!(mailbox.take(7).is_empty());
+    mailbox.store(2, incoming("a"));
+    assert!(mailbox.take(7).is_empty());
+}
+
+#[test]
+fn has_messages() {
+    let mut mailbox = Mailbox::with_capacity(2);
+    assert!(!mailbox.has_messages(2));
+    mailbox.store(2, incoming("a"));
+    assert!(mailbox.has_messages(2));
+    assert!(!mailbox.has_messages(3));
+    mailbox.take(2);
+    assert!(!mailbox.has_messages(2));
+}
+
+#[test]
+fn message_queued() {
+    let msg = ChatResponseMessage::new_message_queued_response(9, 2);
+    assert_eq!(msg.source_id, 9);
+    assert_eq!(msg.content, ChatResponse::MessageQueued(2));
+}
@@ -0,0 +1,114 @@
+/*!
+    This module contains the client side resolver that finds which media server hosts a media file
+*/
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use wg_2024::network::NodeId;
+
+use crate::web_messages::{Compression, RequestMessage};
+
+/// Maps media filenames to the media servers hosting them
+///
+/// it is filled with the `MediaResponse::MediaList` of every known media server
+/// and caches the lists until a server is removed from the network
+#[derive(Debug, Clone, Default)]
+pub struct MediaResolver {
+    /// servers that have been asked for their list and have not answered yet
+    pending: HashSet<NodeId>,
+    /// list of media of every server that answered
+    lists: HashMap<NodeId, Vec<String>>,
+    /// servers hosting every known media
+    index: HashMap<String, BTreeSet<NodeId>>,
+}
+
+impl MediaResolver {
+    /// constructor of an empty resolver
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// produces a `MediaRequest::MediaList` for every media server whose list is neither cached nor pending
+    ///
+    /// returns pairs (media server ID, request)
+    /// * `source_id`: ID of the web client
+    /// * `media_servers`: IDs of the known media servers
+    /// * `compression_type`: compression of the requests
+    pub fn requests(
+        &mut self,
+        source_id: NodeId,
+        media_servers: impl IntoIterator<Item = NodeId>,
+        compression_type: &Compression,
+    ) -> Vec<(NodeId, RequestMessage)> {
+        media_servers
+            .into_iter()
+            .filter(|id| !self.lists.contains_key(id) && self.pending.insert(*id))
+            .map(|id| (id, RequestMessage::new_media_list_request(source_id, compression_type.clone())))
+            .collect()
+    }
+
+    /// stores the list received in a `MediaResponse::MediaList`, replacing the cached one
+    /// * `server_id`: ID of the media server
+    /// * list: media filenames hosted by the server
+    pub fn add_media_list(&mut self, server_id: NodeId, list: Vec<String>) {
+        self.remove_server(server_id);
+        for name in &list {
+            self.index.entry(name.clone()).or_default().insert(server_id);
+        }
+        self.lists.insert(server_id, list);
+    }
+
+    /// returns the ID of a media server hosting the given media, if known
+    /// * name: media filename
+    #[must_use]
+    pub fn resolve(&self, name: &str) -> Option<NodeId> {
+        self.index.get(name).and_then(|servers| servers.first().copied())
+    }
+
+    /// returns the IDs of all the media servers hosting the given media
+    /// * name: media filename
+    #[must_use]
+    pub fn servers_of(&self, name: &str) -> Vec<NodeId> {
+        self.index
+            .get(name)
+            .map(|servers| servers.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// checks if some media server has not answered with its list yet
+    ///
+    /// while true, a media that cannot be resolved may still be found
+    #[inline]
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// invalidates everything known about a server
+    ///
+    /// to be called when the server is removed from the network or its list has to be requested again
+    /// * `server_id`: ID of the media server
+    pub fn remove_server(&mut self, server_id: NodeId) {
+        self.pending.remove(&server_id);
+        for name in self.lists.remove(&server_id).unwrap_or_default() {
+            if let Some(servers) = self.index.get_mut(&name) {
+                servers.remove(&server_id);
+                if servers.is_empty() {
+                    self.index.remove(&name);
+                }
+            }
+        }
+    }
+
+    /// invalidates the whole cache
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.lists.clear();
+        self.index.clear();
+    }
+}
@@ -0,0 +1,47 @@
+use crate::media_resolver::MediaResolver;
+use crate::web_messages::{Compression, MediaRequest, Request};
+
+fn names(list: &[&str]) -> Vec<String> {
+    list.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn resolve() {
+    let mut resolver = MediaResolver::new();
+    let requests = resolver.requests(1, [10, 11], &Compression::None);
+    assert_eq!(requests.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![10, 11]);
+    assert!(requests
+        .iter()
+        .all(|(_, r)| r.source_id == 1 && matches!(r.content, Request::Media(MediaRequest::MediaList))));
+    assert!(resolver.requests(1, [10, 11], &Compression::None).is_empty());
+    assert!(resolver.is_pending());
+
+    resolver.add_media_list(11, names(&["a.png", "b.png"]));
+    resolver.add_media_list(10, names(&["b.png"]));
+    assert!(!resolver.is_pending());
+    assert_eq!(resolver.resolve("a.png"), Some(11));
+    assert_eq!(resolver.resolve("b.png"), Some(10));
+    assert_eq!(resolver.servers_of("b.png"), vec![10, 11]);
+    assert_eq!(resolver.resolve("c.png"), None);
+    assert!(resolver.requests(1, [10, 11], &Compression::None).is_empty());
+}
+
+#[test]
+fn invalidation() {
+    let mut resolver = MediaResolver::new();
+    resolver.add_media_list(10, names(&["a.png", "b.png"]));
+    resolver.add_media_list(11, names(&["b.png"]));
+
+    resolver.remove_server(10);
+    assert_eq!(resolver.resolve("a.png"), None);
+    assert_eq!(resolver.resolve("b.png"), Some(11));
+    assert_eq!(resolver.requests(1, [10, 11], &Compression::None).len(), 1);
+
+    resolver.add_media_list(11, names(&["c.png"]));
+    assert_eq!(resolver.resolve("b.png"), None);
+    assert_eq!(resolver.resolve("c.png"), Some(11));
+
+    resolver.clear();
+    assert_eq!(resolver.resolve("c.png"), None);
+    assert!(!resolver.is_pending());
+}
@@ -0,0 +1,153 @@
+/*!
+    This module contains the client side helpers to download a media file in ranges
+*/
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use wg_2024::network::NodeId;
+
+use crate::checksum::{IntegrityError, VerifiedFile};
+use crate::web_messages::{Compression, RequestMessage};
+
+/// Error generated while assembling a media file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferError {
+    /// the range starting at the given offset does not match the requested ranges
+    InvalidRange(u64),
+    /// some ranges have not been received yet
+    Incomplete,
+    /// the assembled file does not match the checksum announced by the server
+    Integrity(IntegrityError),
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferError::InvalidRange(offset) => write!(f, "Invalid range at offset {offset}"),
+            TransferError::Incomplete => write!(f, "Media file is incomplete"),
+            TransferError::Integrity(e) => write!(f, "{e}"),
+        }
+    }
+}
+impl std::error::Error for TransferError {}
+
+/// Media file being downloaded in ranges of fixed length
+///
+/// built from the `MediaResponse::MediaInfo` of the file, it produces the
+/// `MediaRequest::MediaRange` requests that are still needed and checks the assembled file
+#[derive(Debug, Clone)]
+pub struct MediaDownload {
+    name: String,
+    size: u64,
+    checksum: u32,
+    range_len: u64,
+    ranges: BTreeMap<u64, Vec<u8>>,
+}
+
+impl MediaDownload {
+    /// constructor of a download
+    /// * name: name of the media file
+    /// * size: size of the file from `MediaResponse::MediaInfo`
+    /// * checksum: crc32 of the file from `MediaResponse::MediaInfo`
+    /// * `range_len`: length of every requested range (at least 1)
+    #[must_use]
+    pub fn new(name: String, size: u64, checksum: u32, range_len: u64) -> Self {
+        Self {
+            name,
+            size,
+            checksum,
+            range_len: range_len.max(1),
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    /// Getter of the name of the media file
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// number of ranges the file is split into
+    fn range_count(&self) -> u64 {
+        self.size.div_ceil(self.range_len)
+    }
+
+    /// retrieves the first ranges (offset, length) that have not been received yet
+    ///
+    /// the size comes from the server, so the ranges are produced lazily and at most max of them are returned
+    /// * max: maximum number of ranges to return
+    #[must_use]
+    pub fn missing_ranges(&self, max: usize) -> Vec<(u64, u64)> {
+        (0..self.range_count())
+            .map(|i| i * self.range_len)
+            .filter(|offset| !self.ranges.contains_key(offset))
+            .take(max)
+            .map(|offset| (offset, self.range_len.min(self.size - offset)))
+            .collect()
+    }
+
+    /// builds the requests of the first ranges that have not been received yet
+    /// * `source_id`: ID of the client
+    /// * `compression_type`: compression to be used by the server
+    /// * max: maximum number of requests, i.e. of ranges in flight
+    #[must_use]
+    pub fn requests(&self, source_id: NodeId, compression_type: &Compression, max: usize) -> Vec<RequestMessage> {
+        self.missing_ranges(max)
+            .into_iter()
+            .map(|(offset, length)| {
+                RequestMessage::new_media_range_request(
+                    source_id,
+                    compression_type.clone(),
+                    self.name.clone(),
+                    offset,
+                    length,
+                )
+            })
+            .collect()
+    }
+
+    /// stores a range received in a `MediaResponse::MediaRange`
+    ///
+    /// a range received twice replaces the previous one
+    /// * offset: offset of the range in the file
+    /// * data: content of the range
+    /// # Errors
+    ///
+    /// Will return Err if the range is not one of the ranges produced by `missing_ranges`
+    pub fn add_range(&mut self, offset: u64, data: Vec<u8>) -> Result<(), TransferError> {
+        let expected_len = self.range_len.min(self.size.saturating_sub(offset));
+        if offset >= self.size || !offset.is_multiple_of(self.range_len) || data.len() as u64 != expected_len {
+            return Err(TransferError::InvalidRange(offset));
+        }
+        self.ranges.insert(offset, data);
+        Ok(())
+    }
+
+    /// checks if every range has been received
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        // only valid ranges are stored, one per offset
+        self.ranges.len() as u64 == self.range_count()
+    }
+
+    /// assembles the ranges and verifies the checksum of the file
+    /// # Errors
+    ///
+    /// Will return Err if some ranges are missing or if the checksum does not match,
+    /// in the latter case the ranges are discarded so that they can be requested again
+    pub fn finish(&mut self) -> Result<VerifiedFile, TransferError> {
+        if !self.is_complete() {
+            return Err(TransferError::Incomplete);
+        }
+        let data: Vec<u8> = self.ranges.values().flatten().copied().collect();
+        VerifiedFile::new(self.name.clone(), data, self.checksum).map_err(|e| {
+            self.ranges.clear();
+            TransferError::Integrity(e)
+        })
+    }
+}
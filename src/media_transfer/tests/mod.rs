@@ -0,0 +1,93 @@
+use crate::checksum::{crc32, IntegrityError};
+use crate::media_transfer::{MediaDownload, TransferError};
+use crate::web_messages::{Compression, MediaRequest, Request, ResponseMessage, Serializable};
+
+fn file() -> Vec<u8> {
+    (0..=255u8).cycle().take(1000).collect()
+}
+
+#[test]
+fn crc32_known_values() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+}
+
+#[test]
+fn download_ranges() {
+    let data = file();
+    let mut d = MediaDownload::new("img.png".to_string(), data.len() as u64, crc32(&data), 300);
+    assert_eq!(d.missing_ranges(usize::MAX), vec![(0, 300), (300, 300), (600, 300), (900, 100)]);
+
+    let requests = d.requests(1, &Compression::None, usize::MAX);
+    assert_eq!(requests.len(), 4);
+    assert_eq!(
+        requests[3].content,
+        Request::Media(MediaRequest::MediaRange("img.png".to_string(), 900, 100))
+    );
+
+    for (offset, length) in d.missing_ranges(usize::MAX).into_iter().rev() {
+        let (o, l) = (usize::try_from(offset).unwrap(), usize::try_from(length).unwrap());
+        d.add_range(offset, data[o..o + l].to_vec()).unwrap();
+    }
+    assert!(d.is_complete());
+    assert_eq!(d.finish().unwrap().data(), data);
+}
+
+#[test]
+fn download_invalid_ranges() {
+    let data = file();
+    let mut d = MediaDownload::new("img.png".to_string(), data.len() as u64, crc32(&data), 300);
+    assert_eq!(d.add_range(10, vec![0; 300]), Err(TransferError::InvalidRange(10)));
+    assert_eq!(d.add_range(0, vec![0; 299]), Err(TransferError::InvalidRange(0)));
+    assert_eq!(d.add_range(1200, vec![0; 300]), Err(TransferError::InvalidRange(1200)));
+    assert_eq!(d.finish(), Err(TransferError::Incomplete));
+}
+
+#[test]
+fn download_checksum_mismatch() {
+    let data = file();
+    let mut d = MediaDownload::new("img.png".to_string(), data.len() as u64, crc32(&data), 600);
+    d.add_range(0, data[..600].to_vec()).unwrap();
+    d.add_range(600, vec![0; 400]).unwrap();
+    assert!(matches!(d.finish(), Err(TransferError::Integrity(IntegrityError::DigestMismatch { .. }))));
+    // corrupted ranges have to be downloaded again
+    assert_eq!(d.missing_ranges(usize::MAX).len(), 2);
+}
+
+#[test]
+fn download_empty_file() {
+    let mut d = MediaDownload::new("empty.png".to_string(), 0, crc32(&[]), 128);
+    assert!(d.missing_ranges(usize::MAX).is_empty());
+    assert!(d.finish().unwrap().data().is_empty());
+}
+
+#[test]
+fn response_digest() {
+    let data = file();
+    let msg = ResponseMessage::new_media_response(1, Compression::LZW, data.clone());
+    let msg = ResponseMessage::deserialize(msg.serialize().unwrap()).unwrap();
+    assert_eq!(msg.verify(), Ok(()));
+
+    let mut tampered = msg.clone();
+    tampered.digest = tampered.digest.map(|d| d ^ 1);
+    assert!(matches!(tampered.verify(), Err(IntegrityError::DigestMismatch { .. })));
+    tampered.digest = None;
+    assert_eq!(tampered.verify(), Err(IntegrityError::MissingDigest));
+
+    let file = msg.into_verified_file("img.png".to_string()).unwrap();
+    assert_eq!(file.into_parts(), ("img.png".to_string(), data));
+    assert_eq!(
+        ResponseMessage::new_media_list_response(1, Compression::None, vec![]).into_verified_file(String::new()),
+        Err(IntegrityError::NotAFile)
+    );
+}
+
+#[test]
+fn download_huge_size() {
+    // a bogus size announced by the server must not allocate every range
+    let d = MediaDownload::new("img.png".to_string(), u64::MAX, 0, 1);
+    assert!(!d.is_complete());
+    assert_eq!(d.missing_ranges(3), vec![(0, 1), (1, 1), (2, 1)]);
+    assert_eq!(d.requests(1, &Compression::None, 8).len(), 8);
+}
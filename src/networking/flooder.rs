@@ -3,20 +3,115 @@
     This module contains the Flooder trait which is common to both clients and servers in the network
 */
 
+use std::time::{Duration, Instant};
+
 use crossbeam_channel::Sender;
 use wg_2024::network::{NodeId, SourceRoutingHeader};
-use wg_2024::packet::{FloodRequest, NodeType, Packet};
+use wg_2024::packet::{FloodRequest, FloodResponse, NodeType, Packet};
+
+use super::topology::Topology;
+use crate::ring_buffer::{Clock, SystemClock};
 
 /// Error that is generated during the handling of a flood request
-#[derive(Debug)]
-pub struct FloodingError;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FloodingError {
+    /// the packet could not be sent on the channels of the given neighbours
+    SendFailed(Vec<NodeId>),
+    /// the node has no neighbour to flood
+    NoNeighbours,
+}
 impl std::fmt::Display for FloodingError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Flooding Error")
+        match self {
+            FloodingError::SendFailed(ids) => write!(f, "Flooding Error: cannot send to {ids:?}"),
+            FloodingError::NoNeighbours => write!(f, "Flooding Error: no neighbours"),
+        }
     }
 }
 impl std::error::Error for FloodingError {}
 
+/// Flood started by this node, collects the responses until its window is over
+///
+/// the window is measured with the clock C, the system clock by default
+#[derive(Debug, Clone)]
+pub struct FloodDiscovery<C = SystemClock> {
+    flood_id: u64,
+    started: Instant,
+    window: Duration,
+    responses: Vec<FloodResponse>,
+    /// neighbours the flood request could not be sent to
+    unreachable: Vec<NodeId>,
+    clock: C,
+}
+
+impl FloodDiscovery {
+    /// Constructor of a discovery started now
+    /// * `flood_id`: ID of the flood
+    /// * window: time during which flood responses are collected
+    #[inline]
+    #[must_use]
+    pub fn new(flood_id: u64, window: Duration) -> Self {
+        Self::with_clock(flood_id, window, SystemClock)
+    }
+}
+
+impl<C: Clock> FloodDiscovery<C> {
+    /// Constructor of a discovery started now according to the given clock
+    /// * `flood_id`: ID of the flood
+    /// * window: time during which flood responses are collected
+    /// * clock: source of the current time
+    #[inline]
+    #[must_use]
+    pub fn with_clock(flood_id: u64, window: Duration, clock: C) -> Self {
+        Self {
+            flood_id,
+            started: clock.now(),
+            window,
+            responses: Vec::new(),
+            unreachable: Vec::new(),
+            clock,
+        }
+    }
+
+    /// Getter of the neighbours the flood request could not be sent to
+    #[inline]
+    #[must_use]
+    pub fn unreachable(&self) -> &[NodeId] {
+        &self.unreachable
+    }
+
+    /// Getter of the ID of the flood
+    #[inline]
+    #[must_use]
+    pub fn flood_id(&self) -> u64 {
+        self.flood_id
+    }
+
+    /// checks if the window is over
+    #[inline]
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.clock.now().saturating_duration_since(self.started) >= self.window
+    }
+
+    /// Getter of the responses collected so far
+    #[inline]
+    #[must_use]
+    pub fn responses(&self) -> &[FloodResponse] {
+        &self.responses
+    }
+
+    /// builds the graph of the network from the collected responses
+    #[must_use]
+    pub fn topology(&self) -> Topology {
+        let mut t = Topology::new();
+        for r in &self.responses {
+            t.add_flood_response(r);
+        }
+        t
+    }
+}
+
 /// Gives the ability to handle a flood request in the network
 // ! NOTE we can have the function take a packet but then we would need another enum match
 pub trait Flooder {
@@ -37,11 +132,101 @@ pub trait Flooder {
     /// logs to scl that the packet p has been sent
     /// * p: packet to be logged
     fn send_to_controller(&self, p: Packet);
+    /// asks scl to deliver a packet that cannot be sent through the network
+    /// (`ServerEvent::ShortCut` for servers, `WebClientEvent::Shortcut`/`ChatClientEvent::Shortcut` for clients)
+    /// * p: packet to be shortcut
+    fn send_shortcut(&self, p: Packet);
+
+    /// Provided method that starts a new flood from this node, see `start_flood_with_clock`
+    /// * window: time during which flood responses are collected
+    /// # Errors
+    ///
+    /// Will return Err if the node has no neighbours or if none of them can be reached
+    fn start_flood(&mut self, window: Duration) -> Result<FloodDiscovery, FloodingError> {
+        self.start_flood_with_clock(window, SystemClock)
+    }
+
+    /// Provided method that starts a new flood from this node, measuring its window with the given clock
+    ///
+    /// sends a flood request with a fresh flood ID to every neighbour,
+    /// the neighbours that cannot be reached are listed by `FloodDiscovery::unreachable`
+    /// * window: time during which flood responses are collected
+    /// * clock: source of the current time
+    /// # Errors
+    ///
+    /// Will return Err if the node has no neighbours or if none of them can be reached
+    fn start_flood_with_clock<C: Clock>(&mut self, window: Duration, clock: C) -> Result<FloodDiscovery<C>, FloodingError> {
+        let flood_id: u64 = rand::random();
+        let sid: u64 = rand::random();
+        let flood_r = FloodRequest {
+            flood_id,
+            initiator_id: self.get_id(),
+            path_trace: vec![(self.get_id(), Self::NODE_TYPE)],
+        };
+
+        // the request will come back to us: answer it instead of forwarding it
+        self.insert_flood((self.get_id(), flood_id));
+
+        let it = self.get_neighbours();
+        let neighbours = it.len();
+        if neighbours == 0 {
+            return Err(FloodingError::NoNeighbours);
+        }
+        let mut failed: Vec<NodeId> = it
+            .filter_map(|(id, c)| {
+                let new_packet = Packet::new_flood_request(
+                    SourceRoutingHeader {
+                        hop_index: 0,
+                        hops: Vec::new(),
+                    },
+                    sid,
+                    flood_r.clone(),
+                );
+                match c.send(new_packet.clone()) {
+                    Ok(()) => {
+                        self.send_to_controller(new_packet);
+                        None
+                    }
+                    Err(_) => Some(*id),
+                }
+            })
+            .collect();
+
+        failed.sort_unstable();
+
+        if failed.len() == neighbours {
+            Err(FloodingError::SendFailed(failed))
+        } else {
+            let mut discovery = FloodDiscovery::with_clock(flood_id, window, clock);
+            discovery.unreachable = failed;
+            Ok(discovery)
+        }
+    }
+
+    /// Provided method that handles a flood response addressed to this node
+    ///
+    /// responses to other floods or arrived after the window are ignored,
+    /// returns true when the window is over and the discovery is complete
+    /// * discovery: flood started by `start_flood`
+    /// * `flood_r`: flood response received
+    fn handle_flood_response<C: Clock>(&self, discovery: &mut FloodDiscovery<C>, flood_r: &FloodResponse) -> bool {
+        if discovery.is_complete() {
+            return true;
+        }
+        if flood_r.flood_id == discovery.flood_id {
+            discovery.responses.push(flood_r.clone());
+        }
+        false
+    }
 
     /// Provided method that handles an incoming flood request
+    ///
+    /// a flood response that has no next hop, whose next hop is not a neighbour
+    /// or whose channel is closed is shortcut through scl
     /// # Errors
     ///
-    /// Will return Err if the flood reponse cannot be sent
+    /// Will return Err if the flood request cannot be forwarded to some neighbours
+    /// (the other ones still receive it)
     fn handle_flood_request(
         &mut self,
         routing_header: &SourceRoutingHeader,
@@ -60,29 +245,38 @@ pub trait Flooder {
         if self.has_seen_flood(flood_tuple_id) || it.len() <= 1 {
             let mut new_packet: Packet = flood_r.generate_response(sid);
             new_packet.routing_header.increase_hop_index();
-            let next_hop: NodeId = new_packet
-                .routing_header
-                .current_hop()
-                .expect("If this panics the wg code is borken");
-            match it.find(|(id, c)| **id == next_hop) {
-                Some((_, c)) => {
-                    c.send(new_packet.clone());
+            let next_hop: Option<NodeId> = new_packet.routing_header.current_hop();
+            match next_hop.and_then(|next_hop| it.find(|(id, c)| **id == next_hop)) {
+                Some((_, c)) if c.send(new_packet.clone()).is_ok() => {
                     self.send_to_controller(new_packet);
-                    Ok(())
                 }
-                None => Err(FloodingError),
+                _ => self.send_shortcut(new_packet),
             }
+            Ok(())
         } else {
-            it.for_each(|(id, c)| {
-                if *id != sender_id {
+            let mut failed: Vec<NodeId> = it
+                .filter(|(id, c)| **id != sender_id)
+                .filter_map(|(id, c)| {
                     let new_packet =
                         Packet::new_flood_request(routing_header.clone(), sid, flood_r.clone());
-                    c.send(new_packet.clone());
-                    self.send_to_controller(new_packet);
-                }
-            });
+                    match c.send(new_packet.clone()) {
+                        Ok(()) => {
+                            self.send_to_controller(new_packet);
+                            None
+                        }
+                        Err(_) => Some(*id),
+                    }
+                })
+                .collect();
             self.insert_flood(flood_tuple_id);
-            Ok(())
+
+            failed.sort_unstable();
+
+            if failed.is_empty() {
+                Ok(())
+            } else {
+                Err(FloodingError::SendFailed(failed))
+            }
         }
     }
 }
@@ -0,0 +1,223 @@
+/*!
+    This module contains the functions used to split a message into fragments and to rebuild it once all of them are received
+*/
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::marker::PhantomData;
+
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::{Fragment, Packet, PacketType, FRAGMENT_DSIZE};
+
+use crate::ring_buffer::HashedRingBuffer;
+use crate::web_messages::{Serializable, SerializationError};
+
+/// number of completed messages remembered to detect late duplicates
+const COMPLETED_HISTORY: usize = 64;
+
+/// maximum number of fragments of a message accepted by `Reassembler` (128 MiB of payload)
+pub const MAX_FRAGMENTS: u64 = 1 << 20;
+
+/// maximum number of messages reassembled at the same time, the oldest one is dropped when exceeded
+pub const MAX_PENDING: usize = 64;
+
+/// Error generated while reassembling a message
+#[derive(Debug)]
+pub enum ReassemblyError {
+    /// the packet does not carry a `MsgFragment`
+    NotAFragment,
+    /// the routing header of the packet does not contain the source node
+    UnknownSource,
+    /// the fragment with the given index has already been received
+    /// (it should still be acknowledged, its ACK may have been lost)
+    DuplicateFragment(u64),
+    /// the fragment with the given index is not consistent with the other fragments of the message
+    InvalidFragment(u64),
+    /// the reassembled bytes are not a valid message
+    Deserialization(SerializationError),
+}
+
+impl fmt::Display for ReassemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReassemblyError::NotAFragment => write!(f, "Packet is not a fragment"),
+            ReassemblyError::UnknownSource => write!(f, "Fragment has no source"),
+            ReassemblyError::DuplicateFragment(i) => write!(f, "Duplicate fragment {i}"),
+            ReassemblyError::InvalidFragment(i) => write!(f, "Invalid fragment {i}"),
+            ReassemblyError::Deserialization(e) => write!(f, "{e}"),
+        }
+    }
+}
+impl std::error::Error for ReassemblyError {}
+
+/// serializes a message and splits it into `MsgFragment` packets
+///
+/// every packet gets a copy of the given routing header, so `hop_index` must already be set
+/// * msg: message to be sent
+/// * `routing_header`: route from this node to the destination
+/// * `session_id`: session of the message
+/// # Errors
+///
+/// Will return Err if the message cannot be serialized
+pub fn fragment<T: Serializable>(
+    msg: &T,
+    routing_header: &SourceRoutingHeader,
+    session_id: u64,
+) -> Result<Vec<Packet>, SerializationError> {
+    let data = msg.serialize()?;
+    let total_n_fragments = data.len().div_ceil(FRAGMENT_DSIZE).max(1) as u64;
+
+    let mut chunks: Vec<&[u8]> = data.chunks(FRAGMENT_DSIZE).collect();
+    if chunks.is_empty() {
+        chunks.push(&[]);
+    }
+
+    Ok(chunks
+        .into_iter()
+        .zip(0..)
+        .map(|(chunk, fragment_index)| {
+            let mut buff = [0; FRAGMENT_DSIZE];
+            buff[..chunk.len()].copy_from_slice(chunk);
+            let fragment = Fragment {
+                fragment_index,
+                total_n_fragments,
+                // chunk.len() <= FRAGMENT_DSIZE = 128
+                #[allow(clippy::cast_possible_truncation)]
+                length: chunk.len() as u8,
+                data: buff,
+            };
+            Packet::new_fragment(routing_header.clone(), session_id, fragment)
+        })
+        .collect())
+}
+
+/// Fragments of a message that has not been completely received yet
+///
+/// only the received fragments are stored, so memory does not depend on the announced total
+#[derive(Debug)]
+struct PendingMessage {
+    total: u64,
+    fragments: BTreeMap<u64, Vec<u8>>,
+    /// order in which the first fragment of the message has been received
+    started: u64,
+}
+
+/// Rebuilds messages of type T from their fragments
+///
+/// fragments are grouped by (`session_id`, source) and can arrive in any order
+#[derive(Debug)]
+pub struct Reassembler<T> {
+    pending: HashMap<(u64, NodeId), PendingMessage>,
+    completed: HashedRingBuffer<(u64, NodeId)>,
+    next_start: u64,
+    _message: PhantomData<T>,
+}
+
+impl<T> Default for Reassembler<T> {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+            completed: HashedRingBuffer::with_capacity(COMPLETED_HISTORY),
+            next_start: 0,
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<T: Serializable> Reassembler<T> {
+    /// constructor of an empty reassembler
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// stores the fragment carried by the packet
+    ///
+    /// returns the decoded message when the packet carries its last missing fragment.
+    /// When `MAX_PENDING` messages are already incomplete, a fragment of a new message
+    /// drops the one that started first
+    /// * packet: packet received from the network
+    /// # Errors
+    ///
+    /// Will return Err if the packet is not a valid fragment (e.g. it belongs to a message
+    /// longer than `MAX_FRAGMENTS` fragments), if it is a duplicate
+    /// or if the completed message cannot be deserialized
+    pub fn insert(&mut self, packet: &Packet) -> Result<Option<T>, ReassemblyError> {
+        let PacketType::MsgFragment(fragment) = &packet.pack_type else {
+            return Err(ReassemblyError::NotAFragment);
+        };
+        let source = *packet
+            .routing_header
+            .hops
+            .first()
+            .ok_or(ReassemblyError::UnknownSource)?;
+        let key = (packet.session_id, source);
+        let index = fragment.fragment_index;
+
+        if self.completed.contains(&key) {
+            return Err(ReassemblyError::DuplicateFragment(index));
+        }
+
+        let total = fragment.total_n_fragments;
+        if total > MAX_FRAGMENTS || index >= total {
+            return Err(ReassemblyError::InvalidFragment(index));
+        }
+        let length = usize::from(fragment.length);
+        if length > FRAGMENT_DSIZE {
+            return Err(ReassemblyError::InvalidFragment(index));
+        }
+
+        if !self.pending.contains_key(&key) && self.pending.len() >= MAX_PENDING {
+            self.drop_oldest();
+        }
+        let pending = self.pending.entry(key).or_insert_with(|| PendingMessage {
+            total,
+            fragments: BTreeMap::new(),
+            started: self.next_start,
+        });
+        self.next_start += 1;
+        if pending.total != total {
+            return Err(ReassemblyError::InvalidFragment(index));
+        }
+        if pending.fragments.contains_key(&index) {
+            return Err(ReassemblyError::DuplicateFragment(index));
+        }
+        pending.fragments.insert(index, fragment.data[..length].to_vec());
+
+        if (pending.fragments.len() as u64) < total {
+            return Ok(None);
+        }
+
+        let data: Vec<u8> = self
+            .pending
+            .remove(&key)
+            .into_iter()
+            .flat_map(|p| p.fragments.into_values())
+            .flatten()
+            .collect();
+        self.completed.insert(key);
+        T::deserialize(data)
+            .map(Some)
+            .map_err(ReassemblyError::Deserialization)
+    }
+
+    /// drops the fragments received so far for the given message
+    /// * `session_id`: session of the message
+    /// * source: ID of the node that sent the message
+    pub fn discard(&mut self, session_id: u64, source: NodeId) -> bool {
+        self.pending.remove(&(session_id, source)).is_some()
+    }
+
+    /// drops the message whose first fragment has been received first
+    fn drop_oldest(&mut self) {
+        let oldest = self
+            .pending
+            .iter()
+            .min_by_key(|(_, p)| p.started)
+            .map(|(key, _)| *key);
+        if let Some(key) = oldest {
+            self.pending.remove(&key);
+        }
+    }
+}
@@ -1,4 +1,12 @@
 /*!
-    This module contains the Flooder trait which is common to both clients and servers in the network
+    This module contains the networking code which is common to both clients and servers in the network
 */
+
+#[cfg(test)]
+mod tests;
+
 pub mod flooder;
+pub mod fragmenter;
+pub mod session;
+pub mod topology;
+pub mod router;
@@ -0,0 +1,275 @@
+/*!
+    This module contains the computation of source routes over the discovered topology
+*/
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::NodeType;
+
+use super::topology::Topology;
+
+/// highest drop rate used when computing costs, keeps the cost of a drone finite
+const MAX_DROP_RATE: f64 = 0.99;
+/// cost added to every hop by `PathCost::LowestDropRate` so that shorter routes win ties
+const HOP_COST: f64 = 1e-6;
+
+/// Strategy used to compare routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathCost {
+    /// route with the least number of hops
+    #[default]
+    FewestHops,
+    /// route with the highest probability of delivery given the observed drop rates
+    LowestDropRate,
+}
+
+/// Forwarding statistics observed for a drone
+#[derive(Debug, Clone, Copy, Default)]
+struct DropStats {
+    forwarded: u64,
+    dropped: u64,
+}
+
+/// Route in the priority queue of Dijkstra's algorithm
+#[derive(Debug)]
+struct State {
+    cost: f64,
+    node: NodeId,
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for State {}
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for State {
+    // reversed so that `BinaryHeap` pops the cheapest route first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// Computes `SourceRoutingHeader`s whose intermediate hops are all drones
+#[derive(Debug, Clone, Default)]
+pub struct Router {
+    strategy: PathCost,
+    stats: HashMap<NodeId, DropStats>,
+}
+
+impl Router {
+    /// constructor of a router
+    /// * strategy: how routes are compared
+    #[inline]
+    #[must_use]
+    pub fn new(strategy: PathCost) -> Self {
+        Self {
+            strategy,
+            stats: HashMap::new(),
+        }
+    }
+
+    /// retrieves the strategy used to compare routes
+    #[inline]
+    #[must_use]
+    pub fn strategy(&self) -> PathCost {
+        self.strategy
+    }
+
+    /// changes the strategy used to compare routes
+    #[inline]
+    pub fn set_strategy(&mut self, strategy: PathCost) {
+        self.strategy = strategy;
+    }
+
+    /// records that a packet has been delivered on the route (e.g. its ACK arrived)
+    /// * hops: route of the delivered packet
+    pub fn record_delivered(&mut self, hops: &[NodeId]) {
+        for id in intermediate(hops) {
+            self.stats.entry(*id).or_default().forwarded += 1;
+        }
+    }
+
+    /// records that a packet has been dropped on the route (e.g. a `Dropped` NACK arrived)
+    /// * hops: route of the dropped packet
+    /// * dropper: ID of the drone that dropped the packet
+    pub fn record_dropped(&mut self, hops: &[NodeId], dropper: NodeId) {
+        for id in intermediate(hops).iter().take_while(|id| **id != dropper) {
+            self.stats.entry(*id).or_default().forwarded += 1;
+        }
+        self.stats.entry(dropper).or_default().dropped += 1;
+    }
+
+    /// retrieves the observed drop rate of a drone, 0 if nothing has been observed
+    /// * id: ID of the drone
+    #[must_use]
+    pub fn drop_rate(&self, id: NodeId) -> f64 {
+        match self.stats.get(&id) {
+            Some(s) if s.forwarded + s.dropped > 0 => {
+                // precision loss is irrelevant for a rate
+                #[allow(clippy::cast_precision_loss)]
+                let rate = s.dropped as f64 / (s.forwarded + s.dropped) as f64;
+                rate
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// forgets the statistics of every drone
+    pub fn reset_stats(&mut self) {
+        self.stats.clear();
+    }
+
+    /// computes the best route between two nodes
+    ///
+    /// returns a header with `hop_index` set to 1, ready to be sent by `from`
+    /// * topology: graph of the network
+    /// * from: ID of the node sending the packet
+    /// * to: ID of the destination
+    #[must_use]
+    pub fn route(&self, topology: &Topology, from: NodeId, to: NodeId) -> Option<SourceRoutingHeader> {
+        self.shortest_path(topology, from, to, &HashSet::new(), &HashSet::new())
+            .map(|(_, hops)| header(hops))
+    }
+
+    /// computes up to k loopless routes between two nodes, from the best to the worst (Yen's algorithm)
+    ///
+    /// the routes after the first one can be used for failover
+    /// * topology: graph of the network
+    /// * from: ID of the node sending the packet
+    /// * to: ID of the destination
+    /// * k: maximum number of routes
+    #[must_use]
+    pub fn k_routes(&self, topology: &Topology, from: NodeId, to: NodeId, k: usize) -> Vec<SourceRoutingHeader> {
+        let mut found: Vec<(f64, Vec<NodeId>)> = Vec::new();
+        let mut candidates: Vec<(f64, Vec<NodeId>)> = Vec::new();
+
+        if k == 0 {
+            return Vec::new();
+        }
+        match self.shortest_path(topology, from, to, &HashSet::new(), &HashSet::new()) {
+            Some(p) => found.push(p),
+            None => return Vec::new(),
+        }
+
+        while found.len() < k {
+            let prev = &found[found.len() - 1].1;
+            for i in 0..prev.len() - 1 {
+                let root = &prev[..=i];
+                let excluded_edges: HashSet<(NodeId, NodeId)> = found
+                    .iter()
+                    .filter(|(_, p)| p.len() > i + 1 && p[..=i] == *root)
+                    .map(|(_, p)| (p[i], p[i + 1]))
+                    .collect();
+                let excluded_nodes: HashSet<NodeId> = root[..i].iter().copied().collect();
+
+                if let Some((_, spur)) = self.shortest_path(topology, prev[i], to, &excluded_nodes, &excluded_edges) {
+                    let mut path = root[..i].to_vec();
+                    path.extend(spur);
+                    if !found.iter().chain(&candidates).any(|(_, p)| *p == path) {
+                        candidates.push((self.path_cost(&path), path));
+                    }
+                }
+            }
+
+            let Some(best) = candidates
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+                .map(|(i, _)| i)
+            else {
+                break;
+            };
+            found.push(candidates.swap_remove(best));
+        }
+
+        found.into_iter().map(|(_, hops)| header(hops)).collect()
+    }
+
+    /// cost of moving to `node` on a route that ends in `to`
+    fn hop_cost(&self, node: NodeId, to: NodeId) -> f64 {
+        match self.strategy {
+            PathCost::FewestHops => 1.0,
+            PathCost::LowestDropRate if node == to => HOP_COST,
+            PathCost::LowestDropRate => HOP_COST - (1.0 - self.drop_rate(node).min(MAX_DROP_RATE)).ln(),
+        }
+    }
+
+    /// total cost of a route
+    fn path_cost(&self, path: &[NodeId]) -> f64 {
+        let to = path[path.len() - 1];
+        path[1..].iter().map(|n| self.hop_cost(*n, to)).sum()
+    }
+
+    /// Dijkstra's algorithm restricted to routes whose intermediate nodes are drones
+    fn shortest_path(
+        &self,
+        topology: &Topology,
+        from: NodeId,
+        to: NodeId,
+        excluded_nodes: &HashSet<NodeId>,
+        excluded_edges: &HashSet<(NodeId, NodeId)>,
+    ) -> Option<(f64, Vec<NodeId>)> {
+        if from == to || !topology.contains(from) || !topology.contains(to) {
+            return None;
+        }
+
+        let mut costs: HashMap<NodeId, f64> = HashMap::from([(from, 0.0)]);
+        let mut previous: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut heap = BinaryHeap::from([State { cost: 0.0, node: from }]);
+
+        while let Some(State { cost, node }) = heap.pop() {
+            if node == to {
+                let mut path = vec![to];
+                while let Some(p) = previous.get(&path[path.len() - 1]) {
+                    path.push(*p);
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+            if costs.get(&node).is_some_and(|c| *c < cost) {
+                continue;
+            }
+
+            for next in topology.neighbours(node) {
+                let usable = next == to || topology.node_type(next) == Some(NodeType::Drone);
+                if !usable || next == from || excluded_nodes.contains(&next) || excluded_edges.contains(&(node, next)) {
+                    continue;
+                }
+                let next_cost = cost + self.hop_cost(next, to);
+                if costs.get(&next).is_none_or(|c| next_cost < *c) {
+                    costs.insert(next, next_cost);
+                    previous.insert(next, node);
+                    heap.push(State {
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+/// nodes of a route between the first and the last one
+fn intermediate(hops: &[NodeId]) -> &[NodeId] {
+    match hops.len() {
+        0..=2 => &[],
+        n => &hops[1..n - 1],
+    }
+}
+
+/// header of a route that is about to be sent by its first node
+fn header(hops: Vec<NodeId>) -> SourceRoutingHeader {
+    SourceRoutingHeader { hop_index: 1, hops }
+}
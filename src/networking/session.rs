@@ -0,0 +1,242 @@
+/*!
+    This module contains the session layer that tracks the delivery of the fragments of a message
+*/
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crossbeam_channel::Sender;
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::{Ack, Nack, NackType, Packet, PacketType};
+
+/// number of times a fragment is resent after a `Dropped` NACK before giving up
+pub const DEFAULT_MAX_RETRIES: usize = 16;
+
+/// Error that ends a session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    /// the fragment with the given index has been dropped more than the allowed number of times
+    RetriesExhausted(u64),
+    /// the last hop of the route is a drone
+    DestinationIsDrone,
+    /// a fragment reached the given node which is not the one expected by the route
+    UnexpectedRecipient(NodeId),
+    /// the route has no next hop
+    EmptyRoute,
+    /// the next hop of the route is not a neighbour of this node
+    NeighbourNotFound(NodeId),
+    /// the channel to the given neighbour is closed
+    SendFailed(NodeId),
+    /// the route goes through the given unreachable node and no new route has been provided yet
+    RouteBroken(NodeId),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::RetriesExhausted(i) => write!(f, "Fragment {i} dropped too many times"),
+            SessionError::DestinationIsDrone => write!(f, "Destination is a drone"),
+            SessionError::UnexpectedRecipient(id) => write!(f, "Unexpected recipient {id}"),
+            SessionError::EmptyRoute => write!(f, "Empty route"),
+            SessionError::NeighbourNotFound(id) => write!(f, "Neighbour {id} not found"),
+            SessionError::SendFailed(id) => write!(f, "Cannot send to {id}"),
+            SessionError::RouteBroken(id) => write!(f, "Route broken at {id}"),
+        }
+    }
+}
+impl std::error::Error for SessionError {}
+
+/// State of a session after an ACK
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// some fragments are still waiting for their ACK
+    InProgress,
+    /// every fragment has been acknowledged
+    Completed,
+}
+
+/// What a session did after a NACK
+#[derive(Debug, Clone, PartialEq)]
+pub enum NackOutcome {
+    /// the dropped fragment has been sent again
+    Resent(Packet),
+    /// the route was broken and the pending fragments have been sent again on the new route
+    Rerouted(Vec<Packet>),
+    /// the given node is not reachable and no other route is known: the network has to be
+    /// flooded again (`Flooder::start_flood`) and a new route has to be passed to `Session::reroute`
+    RouteBroken(NodeId),
+    /// the NACK refers to a fragment that has already been acknowledged
+    Ignored,
+}
+
+/// Keeps the fragments of a message until each one of them is acknowledged
+///
+/// the session never reads from the network: the caller forwards it the ACKs and NACKs
+/// with its `session_id` and passes the `packet_send` map of its `Client`/`Server`.
+///
+/// On an `ErrorInRouting` NACK the session asks the caller for a new route through the hook
+/// passed to `handle_nack` (typically `Router::route` on the `Topology` without the crashed node).
+/// When the hook has no route, the session stops sending until the caller floods the network
+/// and calls `reroute` with the new route
+#[derive(Debug)]
+pub struct Session {
+    id: u64,
+    pending: BTreeMap<u64, Packet>,
+    /// unreachable node of the current route, if any
+    broken: Option<NodeId>,
+    retries: HashMap<u64, usize>,
+    max_retries: usize,
+}
+
+impl Session {
+    /// constructor of a session
+    /// * `session_id`: session of the message
+    /// * fragments: `MsgFragment` packets of the message (see `fragmenter::fragment`), other packets are ignored
+    #[must_use]
+    pub fn new(session_id: u64, fragments: Vec<Packet>) -> Self {
+        Self {
+            id: session_id,
+            pending: fragments
+                .into_iter()
+                .filter_map(|p| match &p.pack_type {
+                    PacketType::MsgFragment(f) => Some((f.fragment_index, p)),
+                    _ => None,
+                })
+                .collect(),
+            broken: None,
+            retries: HashMap::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    /// sets the number of times a fragment can be resent after a `Dropped` NACK
+    #[inline]
+    #[must_use]
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// retrieves the ID of the session
+    #[inline]
+    #[must_use]
+    pub fn session_id(&self) -> u64 {
+        self.id
+    }
+
+    /// checks if every fragment has been acknowledged
+    #[inline]
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// sends every fragment that has not been acknowledged yet
+    ///
+    /// returns the sent packets so that they can be logged to scl
+    /// * `packet_send`: channels to the neighbours of the node
+    /// # Errors
+    ///
+    /// Will return Err if the route is broken, if the next hop is not a neighbour or its channel is closed
+    pub fn send_pending(
+        &self,
+        packet_send: &HashMap<NodeId, Sender<Packet>>,
+    ) -> Result<Vec<Packet>, SessionError> {
+        if let Some(id) = self.broken {
+            return Err(SessionError::RouteBroken(id));
+        }
+        self.pending
+            .values()
+            .map(|p| send(p, packet_send).map(|()| p.clone()))
+            .collect()
+    }
+
+    /// marks the acknowledged fragment as delivered
+    /// * ack: ACK received for this session
+    pub fn handle_ack(&mut self, ack: &Ack) -> SessionStatus {
+        self.pending.remove(&ack.fragment_index);
+        self.retries.remove(&ack.fragment_index);
+        if self.is_completed() {
+            SessionStatus::Completed
+        } else {
+            SessionStatus::InProgress
+        }
+    }
+
+    /// reacts to a NACK received for this session
+    ///
+    /// a `Dropped` fragment is sent again on the same route, an `ErrorInRouting`
+    /// asks the hook for a route that avoids the unreachable node and sends the pending fragments on it
+    /// * nack: NACK received for this session
+    /// * `packet_send`: channels to the neighbours of the node
+    /// * `new_route`: hook called with the unreachable node, returns the new route if one is known
+    /// # Errors
+    ///
+    /// Will return Err if the message cannot be delivered on any route
+    /// or if the fragments cannot be sent again
+    pub fn handle_nack(
+        &mut self,
+        nack: &Nack,
+        packet_send: &HashMap<NodeId, Sender<Packet>>,
+        mut new_route: impl FnMut(NodeId) -> Option<SourceRoutingHeader>,
+    ) -> Result<NackOutcome, SessionError> {
+        let Some(packet) = self.pending.get(&nack.fragment_index) else {
+            return Ok(NackOutcome::Ignored);
+        };
+
+        match nack.nack_type {
+            NackType::Dropped => {
+                let retries = self.retries.entry(nack.fragment_index).or_insert(0);
+                *retries += 1;
+                if *retries > self.max_retries {
+                    return Err(SessionError::RetriesExhausted(nack.fragment_index));
+                }
+                send(packet, packet_send)?;
+                Ok(NackOutcome::Resent(packet.clone()))
+            }
+            NackType::ErrorInRouting(id) => {
+                if let Some(header) = new_route(id) {
+                    self.reroute(&header, packet_send).map(NackOutcome::Rerouted)
+                } else {
+                    self.broken = Some(id);
+                    Ok(NackOutcome::RouteBroken(id))
+                }
+            }
+            NackType::DestinationIsDrone => Err(SessionError::DestinationIsDrone),
+            NackType::UnexpectedRecipient(id) => Err(SessionError::UnexpectedRecipient(id)),
+        }
+    }
+
+    /// replaces the route of every fragment that has not been acknowledged yet and sends them again
+    ///
+    /// returns the sent packets so that they can be logged to scl
+    /// * `routing_header`: new route to the destination, with `hop_index` already set
+    /// * `packet_send`: channels to the neighbours of the node
+    /// # Errors
+    ///
+    /// Will return Err if the next hop is not a neighbour or its channel is closed
+    pub fn reroute(
+        &mut self,
+        routing_header: &SourceRoutingHeader,
+        packet_send: &HashMap<NodeId, Sender<Packet>>,
+    ) -> Result<Vec<Packet>, SessionError> {
+        for p in self.pending.values_mut() {
+            p.routing_header = routing_header.clone();
+        }
+        self.broken = None;
+        self.send_pending(packet_send)
+    }
+}
+
+/// sends the packet to the neighbour identified by its current hop
+fn send(packet: &Packet, packet_send: &HashMap<NodeId, Sender<Packet>>) -> Result<(), SessionError> {
+    let next_hop = packet
+        .routing_header
+        .current_hop()
+        .ok_or(SessionError::EmptyRoute)?;
+    packet_send
+        .get(&next_hop)
+        .ok_or(SessionError::NeighbourNotFound(next_hop))?
+        .send(packet.clone())
+        .map_err(|_| SessionError::SendFailed(next_hop))
+}
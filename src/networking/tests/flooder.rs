@@ -0,0 +1,213 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::{FloodRequest, FloodResponse, NodeType, Packet, PacketType};
+
+use crate::networking::flooder::{FloodingError, Flooder};
+use crate::ring_buffer::{Clock, RingBuffer};
+
+/// clock moved forward by hand, shared between the test and the discovery
+#[derive(Clone)]
+struct TestClock(Rc<Cell<Instant>>);
+
+impl TestClock {
+    fn new() -> Self {
+        Self(Rc::new(Cell::new(Instant::now())))
+    }
+
+    fn advance(&self, d: Duration) {
+        self.0.set(self.0.get() + d);
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
+
+struct TestNode {
+    id: NodeId,
+    neighbours: HashMap<NodeId, Sender<Packet>>,
+    floods: RingBuffer<(NodeId, u64)>,
+    logged: RefCell<Vec<Packet>>,
+    shortcut: RefCell<Vec<Packet>>,
+}
+
+impl Flooder for TestNode {
+    const NODE_TYPE: NodeType = NodeType::Client;
+
+    fn get_id(&self) -> NodeId {
+        self.id
+    }
+    fn get_neighbours(&self) -> impl ExactSizeIterator<Item = (&NodeId, &Sender<Packet>)> {
+        self.neighbours.iter()
+    }
+    fn has_seen_flood(&self, flood_id: (NodeId, u64)) -> bool {
+        self.floods.contains(&flood_id)
+    }
+    fn insert_flood(&mut self, flood_id: (NodeId, u64)) {
+        self.floods.insert(flood_id);
+    }
+    fn send_to_controller(&self, p: Packet) {
+        self.logged.borrow_mut().push(p);
+    }
+    fn send_shortcut(&self, p: Packet) {
+        self.shortcut.borrow_mut().push(p);
+    }
+}
+
+fn node(neighbours: &[NodeId]) -> (TestNode, HashMap<NodeId, Receiver<Packet>>) {
+    let mut senders = HashMap::new();
+    let mut receivers = HashMap::new();
+    for id in neighbours {
+        let (s, r) = unbounded();
+        senders.insert(*id, s);
+        receivers.insert(*id, r);
+    }
+    let node = TestNode {
+        id: 1,
+        neighbours: senders,
+        floods: RingBuffer::with_capacity(16),
+        logged: RefCell::new(Vec::new()),
+        shortcut: RefCell::new(Vec::new()),
+    };
+    (node, receivers)
+}
+
+#[test]
+fn start_flood() {
+    let (mut n, receivers) = node(&[11, 12]);
+    let discovery = n.start_flood(Duration::from_secs(30)).unwrap();
+    assert!(n.has_seen_flood((1, discovery.flood_id())));
+    assert!(discovery.unreachable().is_empty());
+    assert_eq!(n.logged.borrow().len(), 2);
+
+    for r in receivers.values() {
+        let PacketType::FloodRequest(f) = r.try_recv().unwrap().pack_type else {
+            panic!("not a flood request");
+        };
+        assert_eq!(f.flood_id, discovery.flood_id());
+        assert_eq!(f.initiator_id, 1);
+        assert_eq!(f.path_trace, vec![(1, NodeType::Client)]);
+    }
+
+    let (mut lonely, _) = node(&[]);
+    assert!(lonely.start_flood(Duration::from_secs(30)).is_err());
+}
+
+#[test]
+fn flood_responses() {
+    let (mut n, _receivers) = node(&[11]);
+    let clock = TestClock::new();
+    let mut discovery = n.start_flood_with_clock(Duration::from_millis(50), clock.clone()).unwrap();
+    let response = FloodResponse {
+        flood_id: discovery.flood_id(),
+        path_trace: vec![(1, NodeType::Client), (11, NodeType::Drone), (21, NodeType::Server)],
+    };
+    let other = FloodResponse {
+        flood_id: discovery.flood_id().wrapping_add(1),
+        path_trace: vec![(1, NodeType::Client), (11, NodeType::Drone), (22, NodeType::Server)],
+    };
+
+    assert!(!n.handle_flood_response(&mut discovery, &response));
+    assert!(!n.handle_flood_response(&mut discovery, &other));
+    assert_eq!(discovery.responses().len(), 1);
+
+    clock.advance(Duration::from_millis(49));
+    assert!(!discovery.is_complete());
+    clock.advance(Duration::from_millis(1));
+    assert!(discovery.is_complete());
+    assert!(n.handle_flood_response(&mut discovery, &response));
+    assert_eq!(discovery.responses().len(), 1);
+    assert!(discovery.topology().servers().contains(&21));
+}
+
+#[test]
+fn start_flood_send_failure() {
+    let (mut n, mut receivers) = node(&[11, 12, 13]);
+    drop(receivers.remove(&12));
+    let discovery = n.start_flood(Duration::from_secs(30)).unwrap();
+    assert_eq!(discovery.unreachable(), &[12]);
+    assert_eq!(receivers[&11].len(), 1);
+    assert_eq!(receivers[&13].len(), 1);
+    assert_eq!(n.logged.borrow().len(), 2);
+
+    let (mut isolated, receivers) = node(&[11, 12]);
+    drop(receivers);
+    assert_eq!(
+        isolated.start_flood(Duration::from_secs(30)).unwrap_err(),
+        FloodingError::SendFailed(vec![11, 12])
+    );
+}
+
+fn empty_route() -> SourceRoutingHeader {
+    SourceRoutingHeader {
+        hop_index: 0,
+        hops: Vec::new(),
+    }
+}
+
+fn flood_request(path_trace: Vec<(NodeId, NodeType)>) -> FloodRequest {
+    FloodRequest {
+        flood_id: 3,
+        initiator_id: path_trace[0].0,
+        path_trace,
+    }
+}
+
+#[test]
+fn forward_send_failure() {
+    let (mut n, mut receivers) = node(&[11, 12, 13, 14, 15, 16]);
+    for id in [16, 12, 15, 14] {
+        drop(receivers.remove(&id));
+    }
+    let mut f = flood_request(vec![(5, NodeType::Client), (11, NodeType::Drone)]);
+
+    // failed neighbours are sorted whatever the order of the neighbours map
+    let res = n.handle_flood_request(&empty_route(), 0, &mut f);
+    assert_eq!(res, Err(FloodingError::SendFailed(vec![12, 14, 15, 16])));
+    // the other neighbours still receive the request, the sender does not
+    assert_eq!(receivers[&13].len(), 1);
+    assert!(receivers[&11].is_empty());
+    assert!(n.has_seen_flood((5, 3)));
+}
+
+#[test]
+fn response_sent_to_previous_hop() {
+    let (mut n, receivers) = node(&[11]);
+    let mut f = flood_request(vec![(5, NodeType::Client), (11, NodeType::Drone)]);
+
+    assert_eq!(n.handle_flood_request(&empty_route(), 0, &mut f), Ok(()));
+    let p = receivers[&11].try_recv().unwrap();
+    assert!(matches!(p.pack_type, PacketType::FloodResponse(_)));
+    assert_eq!(n.logged.borrow().as_slice(), &[p]);
+    assert!(n.shortcut.borrow().is_empty());
+}
+
+#[test]
+fn response_shortcut_when_next_hop_not_neighbour() {
+    let (mut n, receivers) = node(&[12]);
+    let mut f = flood_request(vec![(5, NodeType::Client), (11, NodeType::Drone)]);
+
+    assert_eq!(n.handle_flood_request(&empty_route(), 0, &mut f), Ok(()));
+    assert!(receivers[&12].is_empty());
+    assert!(n.logged.borrow().is_empty());
+    let shortcut = n.shortcut.borrow();
+    assert_eq!(shortcut.len(), 1);
+    assert!(matches!(shortcut[0].pack_type, PacketType::FloodResponse(_)));
+}
+
+#[test]
+fn response_shortcut_when_channel_closed() {
+    let (mut n, mut receivers) = node(&[11]);
+    drop(receivers.remove(&11));
+    let mut f = flood_request(vec![(5, NodeType::Client), (11, NodeType::Drone)]);
+
+    assert_eq!(n.handle_flood_request(&empty_route(), 0, &mut f), Ok(()));
+    assert_eq!(n.shortcut.borrow().len(), 1);
+}
@@ -0,0 +1,137 @@
+use wg_2024::network::SourceRoutingHeader;
+use wg_2024::packet::{Packet, PacketType};
+
+use crate::networking::fragmenter::{fragment, ReassemblyError, Reassembler, MAX_FRAGMENTS, MAX_PENDING};
+use crate::web_messages::{Compression, RequestMessage, ResponseMessage};
+
+fn header() -> SourceRoutingHeader {
+    SourceRoutingHeader {
+        hop_index: 1,
+        hops: vec![1, 11, 12, 21],
+    }
+}
+
+fn fragments_of(msg: &ResponseMessage) -> Vec<Packet> {
+    fragment(msg, &header(), 7).unwrap()
+}
+
+#[test]
+fn fragment_sizes() {
+    let msg = ResponseMessage::new_media_response(21, Compression::None, vec![0xab; 1000]);
+    let packets = fragments_of(&msg);
+    assert!(packets.len() > 1);
+
+    for (i, p) in packets.iter().enumerate() {
+        assert_eq!(p.session_id, 7);
+        assert_eq!(p.routing_header, header());
+        let PacketType::MsgFragment(f) = &p.pack_type else {
+            panic!("not a fragment");
+        };
+        assert_eq!(f.fragment_index, i as u64);
+        assert_eq!(f.total_n_fragments, packets.len() as u64);
+        if i + 1 < packets.len() {
+            assert_eq!(f.length, 128);
+        }
+    }
+}
+
+#[test]
+fn reassemble_out_of_order() {
+    let msg = ResponseMessage::new_text_response(21, Compression::LZW, b"<html>hello</html>".repeat(50));
+    let mut packets = fragments_of(&msg);
+    packets.reverse();
+    let last = packets.pop().unwrap();
+
+    let mut r: Reassembler<ResponseMessage> = Reassembler::new();
+    for p in &packets {
+        assert!(r.insert(p).unwrap().is_none());
+    }
+    assert_eq!(r.insert(&last).unwrap(), Some(msg));
+}
+
+#[test]
+fn reassemble_single_fragment() {
+    let msg = RequestMessage::new_text_list_request(1, Compression::None);
+    let packets = fragment(&msg, &header(), 3).unwrap();
+    assert_eq!(packets.len(), 1);
+
+    let mut r: Reassembler<RequestMessage> = Reassembler::new();
+    assert_eq!(r.insert(&packets[0]).unwrap(), Some(msg));
+}
+
+#[test]
+fn reassemble_duplicates() {
+    let msg = ResponseMessage::new_media_response(21, Compression::None, vec![1; 300]);
+    let packets = fragments_of(&msg);
+
+    let mut r: Reassembler<ResponseMessage> = Reassembler::new();
+    assert!(r.insert(&packets[0]).unwrap().is_none());
+    assert!(matches!(r.insert(&packets[0]), Err(ReassemblyError::DuplicateFragment(0))));
+    for p in &packets[1..] {
+        r.insert(p).unwrap();
+    }
+    assert!(matches!(r.insert(&packets[1]), Err(ReassemblyError::DuplicateFragment(1))));
+}
+
+#[test]
+fn reassemble_interleaved_sessions() {
+    let a = ResponseMessage::new_media_response(21, Compression::None, vec![1; 300]);
+    let b = ResponseMessage::new_media_response(21, Compression::None, vec![2; 300]);
+    let pa = fragment(&a, &header(), 1).unwrap();
+    let pb = fragment(&b, &header(), 2).unwrap();
+
+    let mut r: Reassembler<ResponseMessage> = Reassembler::new();
+    let mut done = Vec::new();
+    for (x, y) in pa.iter().zip(&pb) {
+        done.extend(r.insert(x).unwrap());
+        done.extend(r.insert(y).unwrap());
+    }
+    assert_eq!(done, vec![a, b]);
+}
+
+#[test]
+fn reassemble_invalid_packets() {
+    let mut r: Reassembler<ResponseMessage> = Reassembler::new();
+    let ack = Packet::new_ack(header(), 1, 0);
+    assert!(matches!(r.insert(&ack), Err(ReassemblyError::NotAFragment)));
+
+    let msg = ResponseMessage::new_media_response(21, Compression::None, vec![1; 300]);
+    let mut p = fragments_of(&msg).remove(0);
+    if let PacketType::MsgFragment(f) = &mut p.pack_type {
+        f.fragment_index = f.total_n_fragments;
+    }
+    assert!(matches!(r.insert(&p), Err(ReassemblyError::InvalidFragment(_))));
+}
+
+#[test]
+fn reassemble_too_many_fragments() {
+    let mut r: Reassembler<ResponseMessage> = Reassembler::new();
+    let msg = ResponseMessage::new_media_response(21, Compression::None, vec![1; 300]);
+    for total in [MAX_FRAGMENTS + 1, u64::MAX] {
+        let mut p = fragments_of(&msg).remove(0);
+        if let PacketType::MsgFragment(f) = &mut p.pack_type {
+            f.total_n_fragments = total;
+        }
+        assert!(matches!(r.insert(&p), Err(ReassemblyError::InvalidFragment(0))));
+    }
+    // nothing has been allocated for the rejected message
+    assert!(!r.discard(7, 1));
+}
+
+#[test]
+fn reassemble_pending_limit() {
+    let mut r: Reassembler<ResponseMessage> = Reassembler::new();
+    let msg = ResponseMessage::new_media_response(21, Compression::None, vec![1; 300]);
+    // first fragment of many messages announcing the largest accepted size
+    for session_id in 0..=MAX_PENDING as u64 {
+        let mut p = fragment(&msg, &header(), session_id).unwrap().remove(0);
+        if let PacketType::MsgFragment(f) = &mut p.pack_type {
+            f.total_n_fragments = MAX_FRAGMENTS;
+        }
+        assert!(r.insert(&p).unwrap().is_none());
+    }
+    // the oldest message has been dropped to make room for the last one
+    assert!(!r.discard(0, 1));
+    assert!(r.discard(1, 1));
+    assert!(r.discard(MAX_PENDING as u64, 1));
+}
@@ -0,0 +1,5 @@
+mod fragmenter;
+mod session;
+mod topology;
+mod router;
+mod flooder;
@@ -0,0 +1,94 @@
+use wg_2024::packet::NodeType;
+
+use crate::networking::router::{PathCost, Router};
+use crate::networking::topology::Topology;
+
+fn topology() -> Topology {
+    let mut t = Topology::new();
+    // 1 - 11 - 12 - 13 - 21
+    //      |             |
+    //      14 --------- 15
+    //      |
+    //      2 - 16 - 21
+    t.add_path_trace(&[
+        (1, NodeType::Client),
+        (11, NodeType::Drone),
+        (12, NodeType::Drone),
+        (13, NodeType::Drone),
+        (21, NodeType::Server),
+    ]);
+    t.add_path_trace(&[
+        (1, NodeType::Client),
+        (11, NodeType::Drone),
+        (14, NodeType::Drone),
+        (15, NodeType::Drone),
+        (21, NodeType::Server),
+    ]);
+    t.add_path_trace(&[
+        (1, NodeType::Client),
+        (11, NodeType::Drone),
+        (14, NodeType::Drone),
+        (2, NodeType::Client),
+        (16, NodeType::Drone),
+        (21, NodeType::Server),
+    ]);
+    t
+}
+
+#[test]
+fn route_fewest_hops() {
+    let t = topology();
+    let r = Router::new(PathCost::FewestHops).route(&t, 1, 21).unwrap();
+    assert_eq!(r.hop_index, 1);
+    assert_eq!(r.hops.len(), 5);
+    assert_eq!(r.hops[0], 1);
+    assert_eq!(r.hops[4], 21);
+
+    // clients can't be used as intermediate hops
+    let mut t = t;
+    t.remove_node(15);
+    t.remove_node(13);
+    assert!(Router::default().route(&t, 1, 21).is_none());
+    assert!(Router::default().route(&t, 1, 1).is_none());
+    assert!(Router::default().route(&t, 1, 99).is_none());
+}
+
+#[test]
+fn route_lowest_drop_rate() {
+    let t = topology();
+    let mut router = Router::new(PathCost::LowestDropRate);
+    for _ in 0..10 {
+        router.record_dropped(&[1, 11, 12, 13, 21], 12);
+        router.record_delivered(&[1, 11, 14, 15, 21]);
+    }
+    assert!(router.drop_rate(12) > 0.9);
+    assert!(router.drop_rate(11) < f64::EPSILON);
+
+    assert_eq!(router.route(&t, 1, 21).unwrap().hops, vec![1, 11, 14, 15, 21]);
+    router.reset_stats();
+    for _ in 0..10 {
+        router.record_dropped(&[1, 11, 14, 15, 21], 15);
+    }
+    assert_eq!(router.route(&t, 1, 21).unwrap().hops, vec![1, 11, 12, 13, 21]);
+}
+
+#[test]
+fn route_k_shortest() {
+    let t = topology();
+    let routes = Router::default().k_routes(&t, 1, 21, 5);
+    let hops: Vec<Vec<u8>> = routes.iter().map(|r| r.hops.clone()).collect();
+    assert_eq!(hops.len(), 2);
+    assert!(hops.contains(&vec![1, 11, 12, 13, 21]));
+    assert!(hops.contains(&vec![1, 11, 14, 15, 21]));
+    assert!(routes.iter().all(|r| r.hop_index == 1));
+
+    assert_eq!(Router::default().k_routes(&t, 1, 21, 1).len(), 1);
+    assert!(Router::default().k_routes(&t, 1, 21, 0).is_empty());
+
+    let mut t = t;
+    t.add_edge(12, 15);
+    let hops: Vec<Vec<u8>> = Router::default().k_routes(&t, 1, 21, 5).into_iter().map(|r| r.hops).collect();
+    assert_eq!(hops.len(), 4);
+    assert!(hops[..3].iter().all(|h| h.len() == 5));
+    assert_eq!(hops[3], vec![1, 11, 14, 15, 12, 13, 21]);
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use crossbeam_channel::{unbounded, Receiver};
+use wg_2024::network::{NodeId, SourceRoutingHeader};
+use wg_2024::packet::{Ack, Nack, NackType, NodeType, Packet};
+
+use crate::networking::fragmenter::fragment;
+use crate::networking::router::{PathCost, Router};
+use crate::networking::topology::Topology;
+use crate::networking::session::{NackOutcome, Session, SessionError, SessionStatus};
+use crate::web_messages::{Compression, ResponseMessage};
+
+fn route(hops: Vec<NodeId>) -> SourceRoutingHeader {
+    SourceRoutingHeader { hop_index: 1, hops }
+}
+
+fn setup(n: usize) -> (Session, HashMap<NodeId, crossbeam_channel::Sender<Packet>>, Receiver<Packet>, Receiver<Packet>) {
+    let msg = ResponseMessage::new_media_response(1, Compression::None, vec![5; 128 * n]);
+    let packets = fragment(&msg, &route(vec![1, 11, 21]), 9).unwrap();
+    let (s11, r11) = unbounded();
+    let (s12, r12) = unbounded();
+    let packet_send = HashMap::from([(11, s11), (12, s12)]);
+    (Session::new(9, packets).with_max_retries(2), packet_send, r11, r12)
+}
+
+fn no_route(_: NodeId) -> Option<SourceRoutingHeader> {
+    None
+}
+
+fn nack(fragment_index: u64, nack_type: NackType) -> Nack {
+    Nack {
+        fragment_index,
+        nack_type,
+    }
+}
+
+#[test]
+fn session_acks() {
+    let (mut s, packet_send, r11, _) = setup(3);
+    let sent = s.send_pending(&packet_send).unwrap();
+    assert_eq!(sent.len(), r11.len());
+    let n = sent.len() as u64;
+
+    for i in 0..n - 1 {
+        assert_eq!(s.handle_ack(&Ack { fragment_index: i }), SessionStatus::InProgress);
+    }
+    // duplicated ACKs are harmless
+    assert_eq!(s.handle_ack(&Ack { fragment_index: 0 }), SessionStatus::InProgress);
+    assert_eq!(s.handle_ack(&Ack { fragment_index: n - 1 }), SessionStatus::Completed);
+    assert!(s.is_completed());
+}
+
+#[test]
+fn session_dropped() {
+    let (mut s, packet_send, r11, _) = setup(2);
+    s.send_pending(&packet_send).unwrap();
+    let first = r11.recv().unwrap();
+    r11.try_iter().for_each(drop);
+
+    let NackOutcome::Resent(p) = s.handle_nack(&nack(0, NackType::Dropped), &packet_send, no_route).unwrap() else {
+        panic!("fragment not resent");
+    };
+    assert_eq!(p, first);
+    assert_eq!(r11.try_recv().unwrap(), first);
+
+    s.handle_nack(&nack(0, NackType::Dropped), &packet_send, no_route).unwrap();
+    assert_eq!(
+        s.handle_nack(&nack(0, NackType::Dropped), &packet_send, no_route),
+        Err(SessionError::RetriesExhausted(0))
+    );
+
+    s.handle_ack(&Ack { fragment_index: 1 });
+    assert_eq!(s.handle_nack(&nack(1, NackType::Dropped), &packet_send, no_route), Ok(NackOutcome::Ignored));
+}
+
+#[test]
+fn session_reroute() {
+    let (mut s, packet_send, r11, r12) = setup(2);
+    s.send_pending(&packet_send).unwrap();
+    r11.try_iter().for_each(drop);
+    s.handle_ack(&Ack { fragment_index: 0 });
+
+    assert_eq!(
+        s.handle_nack(&nack(1, NackType::ErrorInRouting(11)), &packet_send, no_route),
+        Ok(NackOutcome::RouteBroken(11))
+    );
+    // nothing is sent on the broken route
+    assert_eq!(s.send_pending(&packet_send), Err(SessionError::RouteBroken(11)));
+    assert!(r11.is_empty());
+    let resent = s.reroute(&route(vec![1, 12, 21]), &packet_send).unwrap();
+    assert_eq!(resent.len(), r12.len());
+    assert!(r11.is_empty());
+    assert!(resent.iter().all(|p| p.routing_header.hops == vec![1, 12, 21]));
+
+    assert_eq!(
+        s.reroute(&route(vec![1, 13, 21]), &packet_send),
+        Err(SessionError::NeighbourNotFound(13))
+    );
+}
+
+#[test]
+fn session_failures() {
+    let (mut s, packet_send, r11, _) = setup(1);
+    assert_eq!(
+        s.handle_nack(&nack(0, NackType::DestinationIsDrone), &packet_send, no_route),
+        Err(SessionError::DestinationIsDrone)
+    );
+    drop(r11);
+    assert_eq!(s.send_pending(&packet_send), Err(SessionError::SendFailed(11)));
+}
+
+#[test]
+fn session_crashed_drone() {
+    // 1 -> 11 -> 21 and 1 -> 12 -> 21, drone 13 joins the network later
+    let mut topology = Topology::new();
+    topology.add_node(1, NodeType::Client);
+    topology.add_node(21, NodeType::Server);
+    for d in [11, 12] {
+        topology.add_node(d, NodeType::Drone);
+        topology.add_edge(1, d);
+        topology.add_edge(d, 21);
+    }
+    let router = Router::new(PathCost::FewestHops);
+    let (s13, r13) = unbounded();
+    let (mut s, mut packet_send, r11, r12) = setup(2);
+    s.send_pending(&packet_send).unwrap();
+    r11.try_iter().for_each(drop);
+
+    // the first drone crashes: the hook finds the route through the other one
+    let mut reroute = |id| {
+        topology.remove_node(id);
+        router.route(&topology, 1, 21)
+    };
+    let Ok(NackOutcome::Rerouted(resent)) = s.handle_nack(&nack(0, NackType::ErrorInRouting(11)), &packet_send, &mut reroute) else {
+        panic!("session not rerouted");
+    };
+    assert_eq!(resent.len(), r12.len());
+    assert!(resent.iter().all(|p| p.routing_header.hops == vec![1, 12, 21]));
+    r12.try_iter().for_each(drop);
+    let last = resent.len() as u64 - 1;
+    for i in 0..last {
+        s.handle_ack(&Ack { fragment_index: i });
+    }
+
+    // the second drone crashes too: no route is left until the network is flooded again
+    assert_eq!(
+        s.handle_nack(&nack(last, NackType::ErrorInRouting(12)), &packet_send, &mut reroute),
+        Ok(NackOutcome::RouteBroken(12))
+    );
+    assert_eq!(s.send_pending(&packet_send), Err(SessionError::RouteBroken(12)));
+
+    topology.add_node(13, NodeType::Drone);
+    topology.add_edge(1, 13);
+    topology.add_edge(13, 21);
+    packet_send.insert(13, s13);
+    let header = router.route(&topology, 1, 21).unwrap();
+    assert_eq!(s.reroute(&header, &packet_send).unwrap().len(), 1);
+    assert_eq!(r13.len(), 1);
+    assert_eq!(s.handle_ack(&Ack { fragment_index: last }), SessionStatus::Completed);
+}
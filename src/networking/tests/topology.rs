@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use wg_2024::packet::{FloodResponse, NodeType};
+
+use crate::networking::topology::Topology;
+
+fn topology() -> Topology {
+    let mut t = Topology::new();
+    // 1 - 11 - 12 - 21
+    //      |        |
+    //      13 ------
+    //      |
+    //      2
+    t.add_path_trace(&[(1, NodeType::Client), (11, NodeType::Drone), (12, NodeType::Drone), (21, NodeType::Server)]);
+    t.add_flood_response(&FloodResponse {
+        flood_id: 0,
+        path_trace: vec![(1, NodeType::Client), (11, NodeType::Drone), (13, NodeType::Drone), (21, NodeType::Server)],
+    });
+    t.add_path_trace(&[(1, NodeType::Client), (11, NodeType::Drone), (13, NodeType::Drone), (2, NodeType::Client)]);
+    t
+}
+
+fn sorted(it: impl Iterator<Item = u8>) -> Vec<u8> {
+    let mut v: Vec<u8> = it.collect();
+    v.sort_unstable();
+    v
+}
+
+#[test]
+fn topology_merge() {
+    let t = topology();
+    assert_eq!(sorted(t.nodes()), vec![1, 2, 11, 12, 13, 21]);
+    assert_eq!(sorted(t.neighbours(11)), vec![1, 12, 13]);
+    assert_eq!(sorted(t.neighbours(13)), vec![2, 11, 21]);
+    assert_eq!(t.servers(), HashSet::from([21]));
+    assert_eq!(t.clients(), HashSet::from([1, 2]));
+    assert_eq!(t.drones(), HashSet::from([11, 12, 13]));
+    assert_eq!(t.node_type(12), Some(NodeType::Drone));
+    assert_eq!(t.node_type(99), None);
+}
+
+#[test]
+fn topology_crash() {
+    let mut t = topology();
+    assert_eq!(t.remove_node(13), Some(NodeType::Drone));
+    assert!(!t.contains(13));
+    assert_eq!(sorted(t.neighbours(11)), vec![1, 12]);
+    assert_eq!(sorted(t.neighbours(21)), vec![12]);
+    assert_eq!(t.neighbours(2).count(), 0);
+    assert_eq!(t.remove_node(13), None);
+
+    assert!(t.remove_edge(12, 21));
+    assert!(!t.remove_edge(12, 21));
+    assert_eq!(t.neighbours(21).count(), 0);
+}
@@ -0,0 +1,150 @@
+/*!
+    This module contains the graph of the network discovered through flooding
+*/
+
+use std::collections::{HashMap, HashSet};
+
+use wg_2024::network::NodeId;
+use wg_2024::packet::{FloodResponse, NodeType};
+
+/// Undirected graph of the nodes discovered from `FloodResponse` path traces
+#[derive(Debug, Clone, Default)]
+pub struct Topology {
+    nodes: HashMap<NodeId, NodeType>,
+    edges: HashMap<NodeId, HashSet<NodeId>>,
+}
+
+impl Topology {
+    /// constructor of an empty topology
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// merges a path trace into the graph
+    ///
+    /// every node of the trace is added with its type and linked to the next one
+    /// * `path_trace`: list of (node ID, node type) traversed by a flood request
+    pub fn add_path_trace(&mut self, path_trace: &[(NodeId, NodeType)]) {
+        for &(id, t) in path_trace {
+            self.add_node(id, t);
+        }
+        for pair in path_trace.windows(2) {
+            self.add_edge(pair[0].0, pair[1].0);
+        }
+    }
+
+    /// merges the path trace of a flood response into the graph
+    /// * `flood_r`: flood response received by this node
+    pub fn add_flood_response(&mut self, flood_r: &FloodResponse) {
+        self.add_path_trace(&flood_r.path_trace);
+    }
+
+    /// adds a node or updates its type
+    /// * id: ID of the node
+    /// * `node_type`: type of the node
+    pub fn add_node(&mut self, id: NodeId, node_type: NodeType) {
+        self.nodes.insert(id, node_type);
+        self.edges.entry(id).or_default();
+    }
+
+    /// adds a link between two nodes, nodes that are not known yet are added without a type
+    /// * a: ID of the first node
+    /// * b: ID of the second node
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId) {
+        if a == b {
+            return;
+        }
+        self.edges.entry(a).or_default().insert(b);
+        self.edges.entry(b).or_default().insert(a);
+    }
+
+    /// removes the link between two nodes, returns false if there was no such link
+    /// * a: ID of the first node
+    /// * b: ID of the second node
+    pub fn remove_edge(&mut self, a: NodeId, b: NodeId) -> bool {
+        let removed = self.edges.get_mut(&a).is_some_and(|n| n.remove(&b));
+        self.edges.get_mut(&b).is_some_and(|n| n.remove(&a)) || removed
+    }
+
+    /// removes a crashed node together with all its links
+    ///
+    /// returns the type of the node if it was known
+    /// * id: ID of the crashed node
+    pub fn remove_node(&mut self, id: NodeId) -> Option<NodeType> {
+        if let Some(neighbours) = self.edges.remove(&id) {
+            for n in neighbours {
+                if let Some(e) = self.edges.get_mut(&n) {
+                    e.remove(&id);
+                }
+            }
+        }
+        self.nodes.remove(&id)
+    }
+
+    /// removes every node and link
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.edges.clear();
+    }
+
+    /// checks if the node is in the graph
+    /// * id: ID of the node
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.edges.contains_key(&id)
+    }
+
+    /// retrieves the type of the node, if known
+    /// * id: ID of the node
+    #[inline]
+    #[must_use]
+    pub fn node_type(&self, id: NodeId) -> Option<NodeType> {
+        self.nodes.get(&id).copied()
+    }
+
+    /// retrieves the neighbours of the node
+    /// * id: ID of the node
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges.get(&id).into_iter().flatten().copied()
+    }
+
+    /// retrieves all the nodes in the graph
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges.keys().copied()
+    }
+
+    /// retrieves the IDs of the nodes of the given type
+    /// * `node_type`: type of the nodes to retrieve
+    #[must_use]
+    pub fn nodes_of_type(&self, node_type: NodeType) -> HashSet<NodeId> {
+        self.nodes
+            .iter()
+            .filter(|(_, t)| **t == node_type)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// retrieves the IDs of the servers in the graph
+    #[inline]
+    #[must_use]
+    pub fn servers(&self) -> HashSet<NodeId> {
+        self.nodes_of_type(NodeType::Server)
+    }
+
+    /// retrieves the IDs of the clients in the graph
+    #[inline]
+    #[must_use]
+    pub fn clients(&self) -> HashSet<NodeId> {
+        self.nodes_of_type(NodeType::Client)
+    }
+
+    /// retrieves the IDs of the drones in the graph
+    #[inline]
+    #[must_use]
+    pub fn drones(&self) -> HashSet<NodeId> {
+        self.nodes_of_type(NodeType::Drone)
+    }
+}
@@ -0,0 +1,239 @@
+/*!
+    This module contains a lock-free ring buffer shared between threads
+*/
+
+use std::cell::UnsafeCell;
+use std::cmp::Ordering as Cmp;
+use std::hint;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// slot of the buffer
+///
+/// the sequence tells the state of the slot for the position p it is expected at:
+/// 2p when empty and waiting to be written, 2p + 1 when holding the element of position p
+struct Slot<T> {
+    seq: AtomicU64,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// bounded multi-producer multi-consumer ring buffer that does not need locks
+///
+/// like `RingBuffer::insert`, inserting into a full buffer evicts the first (oldest) element.
+/// It is meant to be shared through an `Arc` between the threads of a node,
+/// e.g. the controller loop and the packet loop of `Server::run`
+pub struct ConcurrentRingBuffer<T> {
+    slots: Box<[Slot<T>]>,
+    /// position of the next element to remove
+    head: AtomicU64,
+    /// position of the next element to insert
+    tail: AtomicU64,
+}
+
+// SAFETY: an element is moved into the buffer by one thread and out of it by exactly one other thread,
+// the slot sequences order these accesses so the buffer can be shared whenever T can be sent
+unsafe impl<T: Send> Send for ConcurrentRingBuffer<T> {}
+unsafe impl<T: Send> Sync for ConcurrentRingBuffer<T> {}
+
+/// signed distance between two sequences, robust to wrapping
+#[inline]
+fn diff(a: u64, b: u64) -> i64 {
+    a.wrapping_sub(b).cast_signed()
+}
+
+impl<T> ConcurrentRingBuffer<T> {
+    /// constructor that allocate a buffer with specified capacity
+    ///
+    /// a buffer with capacity 0 holds nothing
+    /// * size: requested capacity
+    #[must_use]
+    pub fn with_capacity(size: usize) -> Self {
+        Self {
+            slots: (0..size as u64)
+                .map(|i| Slot {
+                    seq: AtomicU64::new(2 * i),
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                })
+                .collect(),
+            head: AtomicU64::new(0),
+            tail: AtomicU64::new(0),
+        }
+    }
+
+    /// returns the maximum number of elements the buffer can hold
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// returns the number of elements in the buffer
+    ///
+    /// the value may already be outdated when other threads are using the buffer
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+        usize::try_from(diff(tail, head).max(0))
+            .unwrap_or(usize::MAX)
+            .min(self.capacity())
+    }
+
+    /// checks if the buffer is empty
+    ///
+    /// the value may already be outdated when other threads are using the buffer
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn slot(&self, pos: u64) -> &Slot<T> {
+        // the remainder is lower than the number of slots, so it fits in usize
+        #[allow(clippy::cast_possible_truncation)]
+        &self.slots[(pos % self.slots.len() as u64) as usize]
+    }
+
+    /// inserts an element in the buffer as the last element
+    ///
+    /// removes the first element if buffer is full and returns it,
+    /// a buffer with capacity 0 returns the inserted element.
+    /// If the first element is being removed by another thread, waits until its slot is released
+    /// * e: element to insert in the buffer
+    pub fn insert(&self, e: T) -> Option<T> {
+        if self.slots.is_empty() {
+            return Some(e);
+        }
+        let cap = self.slots.len() as u64;
+        let mut tail = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(tail);
+            let seq = slot.seq.load(Ordering::Acquire);
+            match diff(seq, 2 * tail).cmp(&0) {
+                Cmp::Equal => match self
+                    .tail
+                    .compare_exchange_weak(tail, tail + 1, Ordering::Relaxed, Ordering::Relaxed)
+                {
+                    Ok(_) => {
+                        // SAFETY: winning the exchange gives this thread exclusive access to the empty slot
+                        unsafe { (*slot.value.get()).write(e) };
+                        slot.seq.store(2 * tail + 1, Ordering::Release);
+                        return None;
+                    }
+                    Err(t) => tail = t,
+                },
+                Cmp::Less => {
+                    // the slot still holds the element of the previous lap: the buffer is full
+                    let oldest = tail.wrapping_sub(cap);
+                    if seq == 2 * oldest + 1
+                        && self
+                            .head
+                            .compare_exchange(oldest, oldest + 1, Ordering::Relaxed, Ordering::Relaxed)
+                            .is_ok()
+                    {
+                        // no producer can take this position until the slot is released,
+                        // so the tail is still the one loaded
+                        self.tail.store(tail + 1, Ordering::Relaxed);
+                        // SAFETY: winning the exchange gives this thread exclusive access to the full slot,
+                        // whose value has been written before the sequence was released
+                        let old = unsafe { (*slot.value.get()).assume_init_read() };
+                        unsafe { (*slot.value.get()).write(e) };
+                        slot.seq.store(2 * tail + 1, Ordering::Release);
+                        return Some(old);
+                    }
+                    // the oldest element is being written or removed by another thread
+                    hint::spin_loop();
+                    tail = self.tail.load(Ordering::Relaxed);
+                }
+                // another thread inserted at this position
+                Cmp::Greater => tail = self.tail.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    /// removes the first element and returns it
+    pub fn pop(&self) -> Option<T> {
+        self.claim().map(Claimed::take)
+    }
+
+    /// reserves the first element, that stays in its slot until the returned guard is consumed
+    pub(super) fn claim(&self) -> Option<Claimed<'_, T>> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            let seq = self.slot(head).seq.load(Ordering::Acquire);
+            match diff(seq, 2 * head + 1).cmp(&0) {
+                Cmp::Equal => match self
+                    .head
+                    .compare_exchange_weak(head, head + 1, Ordering::Relaxed, Ordering::Relaxed)
+                {
+                    Ok(_) => {
+                        return Some(Claimed {
+                            buffer: self,
+                            pos: head,
+                        })
+                    }
+                    Err(h) => head = h,
+                },
+                // the slot is empty or its element is still being written
+                Cmp::Less => return None,
+                // another thread removed the element at this position
+                Cmp::Greater => head = self.head.load(Ordering::Relaxed),
+            }
+        }
+    }
+}
+
+/// first element of a `ConcurrentRingBuffer` reserved by a consumer
+///
+/// the slot is released when the element is taken or the guard is dropped,
+/// until then producers that find the buffer full wait for it
+pub(super) struct Claimed<'a, T> {
+    buffer: &'a ConcurrentRingBuffer<T>,
+    pos: u64,
+}
+
+impl<T> Claimed<'_, T> {
+    /// moves the element out of the slot and releases it
+    pub(super) fn take(self) -> T {
+        let claimed = ManuallyDrop::new(self);
+        claimed.release()
+    }
+
+    /// must be called exactly once, by `take` or `drop`
+    fn release(&self) -> T {
+        let slot = self.buffer.slot(self.pos);
+        // SAFETY: winning the exchange of the head gave this guard exclusive access to the full slot,
+        // whose value has been written before the sequence was released
+        let e = unsafe { (*slot.value.get()).assume_init_read() };
+        slot.seq.store(
+            2 * (self.pos + self.buffer.slots.len() as u64),
+            Ordering::Release,
+        );
+        e
+    }
+}
+
+impl<T> Drop for Claimed<'_, T> {
+    fn drop(&mut self) {
+        drop(self.release());
+    }
+}
+
+impl<T> Drop for ConcurrentRingBuffer<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T> std::fmt::Debug for ConcurrentRingBuffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConcurrentRingBuffer")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,139 @@
+/*!
+    This module contains a set whose elements expire by age and by count
+*/
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// source of the current time used by `ExpiringSet`
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// clock that reads the system monotonic time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// set whose elements expire after a time to live or when more than a maximum number of elements are inserted
+///
+/// meant to remember floods and sessions already seen: a burst can only evict
+/// the oldest elements once the maximum number is reached
+#[derive(Debug)]
+pub struct ExpiringSet<T, C = SystemClock> {
+    /// elements sorted by insertion time
+    entries: VecDeque<(Instant, T)>,
+    /// insertion time of every element
+    index: HashMap<T, Instant>,
+    ttl: Duration,
+    max_len: usize,
+    clock: C,
+}
+
+impl<T: Hash + Eq + Clone> ExpiringSet<T> {
+    /// constructor of an empty set using the system clock
+    /// * ttl: time after which an element expires
+    /// * `max_len`: maximum number of elements, the oldest is evicted when exceeded
+    #[inline]
+    #[must_use]
+    pub fn new(ttl: Duration, max_len: usize) -> Self {
+        Self::with_clock(ttl, max_len, SystemClock)
+    }
+}
+
+impl<T: Hash + Eq + Clone, C: Clock> ExpiringSet<T, C> {
+    /// constructor of an empty set using the given clock
+    /// * ttl: time after which an element expires
+    /// * `max_len`: maximum number of elements, the oldest is evicted when exceeded
+    /// * clock: source of the current time
+    #[inline]
+    #[must_use]
+    pub fn with_clock(ttl: Duration, max_len: usize, clock: C) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            index: HashMap::new(),
+            ttl,
+            max_len,
+            clock,
+        }
+    }
+
+    /// inserts an element, returns false if it was already in the set and has not expired
+    /// or if the maximum number of elements is 0
+    ///
+    /// an element already in the set keeps its insertion time
+    /// * e: element to insert in the set
+    pub fn insert(&mut self, e: T) -> bool {
+        let now = self.clock.now();
+        self.expire_at(now);
+        if self.index.contains_key(&e) || self.max_len == 0 {
+            return false;
+        }
+        if self.entries.len() == self.max_len {
+            self.pop();
+        }
+        self.index.insert(e.clone(), now);
+        self.entries.push_back((now, e));
+        true
+    }
+
+    /// check if the set contains the passed element and it has not expired
+    /// * e: element to be search inside the set
+    #[must_use]
+    pub fn contains(&self, e: &T) -> bool {
+        let now = self.clock.now();
+        self.index.get(e).is_some_and(|t| !self.is_expired(*t, now))
+    }
+
+    /// returns the number of elements that have not expired
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let now = self.clock.now();
+        let expired = self.entries.partition_point(|(t, _)| self.is_expired(*t, now));
+        self.entries.len() - expired
+    }
+
+    /// checks if every element has expired
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// removes the expired elements, freeing their memory
+    ///
+    /// `insert` already does it, so it is only needed after a long idle period
+    pub fn expire(&mut self) {
+        let now = self.clock.now();
+        self.expire_at(now);
+    }
+
+    /// removes every element
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.index.clear();
+    }
+
+    fn is_expired(&self, inserted: Instant, now: Instant) -> bool {
+        now.saturating_duration_since(inserted) >= self.ttl
+    }
+
+    fn expire_at(&mut self, now: Instant) {
+        while self.entries.front().is_some_and(|(t, _)| self.is_expired(*t, now)) {
+            self.pop();
+        }
+    }
+
+    fn pop(&mut self) {
+        if let Some((_, e)) = self.entries.pop_front() {
+            self.index.remove(&e);
+        }
+    }
+}
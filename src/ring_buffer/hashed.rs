@@ -0,0 +1,93 @@
+/*!
+    This module contains a ring buffer that keeps a hash index of its elements
+*/
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use super::PREALLOC_LIMIT;
+
+/// ring buffer that keeps a hash index of its elements
+///
+/// `contains` is constant time instead of a linear scan,
+/// at the cost of a clone of every element stored in the index
+#[derive(Debug)]
+pub struct HashedRingBuffer<T> {
+    buff: VecDeque<T>,
+    /// number of occurrences of every element in the buffer
+    index: HashMap<T, usize>,
+    size: usize,
+}
+
+impl<T: Hash + Eq + Clone> HashedRingBuffer<T> {
+    /// constructor that allocate a buffer with specified capacity
+    /// * size: requested capacity
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(size: usize) -> Self {
+        let prealloc = if size < PREALLOC_LIMIT { size } else { 0 };
+        Self {
+            buff: VecDeque::with_capacity(prealloc),
+            index: HashMap::with_capacity(prealloc),
+            size,
+        }
+    }
+
+    /// inserts an element in the buffer as the last element
+    ///
+    /// removes the first element if buffer is full and returns it,
+    /// a buffer with capacity 0 returns the inserted element
+    /// * e: element to insert in the buffer
+    pub fn insert(&mut self, e: T) -> Option<T> {
+        if self.size == 0 {
+            return Some(e);
+        }
+        let mut ret: Option<T> = None;
+        if self.is_full() {
+            ret = self.pop();
+        }
+
+        *self.index.entry(e.clone()).or_insert(0) += 1;
+        self.buff.push_back(e);
+        ret
+    }
+
+    /// removes the first element and returns it
+    pub fn pop(&mut self) -> Option<T> {
+        let e = self.buff.pop_front()?;
+        self.unindex(&e);
+        Some(e)
+    }
+
+    /// checks if the buffer is empty
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.buff.is_empty()
+    }
+
+    /// check if the buffer is full
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.buff.len() == self.size
+    }
+
+    /// check if the buffer contains the passed element in constant time
+    /// * e: element to be search inside the buffer
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, e: &T) -> bool {
+        self.index.contains_key(e)
+    }
+
+    /// removes one occurrence of the element from the index
+    fn unindex(&mut self, e: &T) {
+        if let Some(count) = self.index.get_mut(e) {
+            *count -= 1;
+            if *count == 0 {
+                self.index.remove(e);
+            }
+        }
+    }
+}
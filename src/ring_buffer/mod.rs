@@ -7,7 +7,19 @@
 #[cfg(test)]
 mod tests;
 
-use std::collections::VecDeque;
+mod concurrent;
+mod expiring;
+mod hashed;
+
+use std::collections::{vec_deque, VecDeque};
+use std::ops::Index;
+
+pub use concurrent::ConcurrentRingBuffer;
+pub use expiring::{Clock, ExpiringSet, SystemClock};
+pub use hashed::HashedRingBuffer;
+
+/// capacity from which `with_capacity` does not preallocate memory
+pub const PREALLOC_LIMIT: usize = 0x400;
 
 /// ring buffer structure templated over the generic type T
 #[derive(Debug)]
@@ -18,26 +30,38 @@ pub struct RingBuffer<T> {
 
 impl<T> RingBuffer<T> {
     /// constructor that allocate a buffer with specified capacity
+    ///
+    /// memory is preallocated only for capacities lower than `PREALLOC_LIMIT`,
+    /// a buffer with capacity 0 holds nothing
     /// * size: requested capacity
     #[inline]
     #[must_use]
     pub fn with_capacity(size: usize) -> Self {
+        Self::with_preallocation(size, if size < PREALLOC_LIMIT { size } else { 0 })
+    }
+
+    /// constructor that allocate a buffer with specified capacity, preallocating memory for the given number of elements
+    /// * size: requested capacity
+    /// * prealloc: number of elements to preallocate, at most size
+    #[inline]
+    #[must_use]
+    pub fn with_preallocation(size: usize, prealloc: usize) -> Self {
         Self {
-            buff: if size < 0x400 {
-                VecDeque::with_capacity(size)
-            } else {
-                VecDeque::new()
-            },
+            buff: VecDeque::with_capacity(prealloc.min(size)),
             size,
         }
     }
 
     /// inserts an element in the buffer as the last element
     ///
-    /// removes the first element if buffer is full and returns it
+    /// removes the first element if buffer is full and returns it,
+    /// a buffer with capacity 0 returns the inserted element
     /// * e: element to insert in the buffer
     #[inline]
     pub fn insert(&mut self, e: T) -> Option<T> {
+        if self.size == 0 {
+            return Some(e);
+        }
         let mut ret: Option<T> = None;
         if self.is_full() {
             ret = self.buff.pop_front();
@@ -66,6 +90,65 @@ impl<T> RingBuffer<T> {
     pub fn is_full(&self) -> bool {
         self.buff.len() == self.size
     }
+
+    /// returns the number of elements in the buffer
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.buff.len()
+    }
+
+    /// returns the maximum number of elements the buffer can hold
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.size
+    }
+
+    /// returns an iterator over the elements, from the first (oldest) to the last (newest)
+    #[inline]
+    #[must_use]
+    pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+        self.buff.iter()
+    }
+
+    /// returns the first (oldest) element without removing it
+    #[inline]
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.buff.front()
+    }
+
+    /// returns the last (newest) element without removing it
+    #[inline]
+    #[must_use]
+    pub fn peek_back(&self) -> Option<&T> {
+        self.buff.back()
+    }
+
+    /// changes the capacity of the buffer
+    ///
+    /// returns the first (oldest) elements that do not fit in the new capacity
+    /// * size: new capacity
+    pub fn set_capacity(&mut self, size: usize) -> Vec<T> {
+        self.size = size;
+        let excess = self.buff.len().saturating_sub(size);
+        let evicted = self.buff.drain(..excess).collect();
+        self.buff.shrink_to(size);
+        evicted
+    }
+
+    /// removes every element, keeping the capacity
+    #[inline]
+    pub fn clear(&mut self) {
+        self.buff.clear();
+    }
+
+    /// removes every element and returns them from the first (oldest) to the last (newest)
+    #[inline]
+    pub fn drain(&mut self) -> vec_deque::Drain<'_, T> {
+        self.buff.drain(..)
+    }
 }
 
 impl<T: PartialEq> RingBuffer<T> {
@@ -75,3 +158,54 @@ impl<T: PartialEq> RingBuffer<T> {
         self.buff.contains(e)
     }
 }
+
+impl<T> Index<usize> for RingBuffer<T> {
+    type Output = T;
+
+    /// returns the element at the given position, 0 being the first (oldest) element
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the index is out of bounds
+    fn index(&self, index: usize) -> &T {
+        &self.buff[index]
+    }
+}
+
+impl<T> IntoIterator for RingBuffer<T> {
+    type Item = T;
+    type IntoIter = vec_deque::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.buff.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.buff.iter()
+    }
+}
+
+impl<T> Extend<T> for RingBuffer<T> {
+    /// inserts every element, evicting the oldest ones when the buffer is full
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for e in iter {
+            self.insert(e);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for RingBuffer<T> {
+    /// builds a full buffer whose capacity is the number of collected elements
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let buff: VecDeque<T> = iter.into_iter().collect();
+        Self {
+            size: buff.len(),
+            buff,
+        }
+    }
+}
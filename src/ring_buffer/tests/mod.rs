@@ -1,4 +1,31 @@
-use crate::ring_buffer::RingBuffer;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::ring_buffer::{Clock, ConcurrentRingBuffer, ExpiringSet, HashedRingBuffer, RingBuffer};
+
+/// clock moved forward by hand, shared between the test and the set
+#[derive(Clone)]
+struct TestClock(Rc<Cell<Instant>>);
+
+impl TestClock {
+    fn new() -> Self {
+        Self(Rc::new(Cell::new(Instant::now())))
+    }
+
+    fn advance(&self, d: Duration) {
+        self.0.set(self.0.get() + d);
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
 
 #[test]
 fn test1() {
@@ -53,3 +80,331 @@ fn test3() {
 
     assert!(rb.pop().is_none())
 }
+
+#[test]
+fn hashed_contains() {
+    let mut rb: HashedRingBuffer<(u64, u64)> = HashedRingBuffer::with_capacity(4);
+    for i in 0..6 {
+        rb.insert((i, 0));
+    }
+    assert!(!rb.contains(&(0, 0)));
+    assert!(!rb.contains(&(1, 0)));
+    assert!(rb.contains(&(2, 0)));
+    assert!(rb.contains(&(5, 0)));
+
+    // duplicates stay indexed until the last one is evicted
+    rb.insert((5, 0));
+    assert_eq!(rb.insert((5, 0)), Some((3, 0)));
+    assert_eq!(rb.pop(), Some((4, 0)));
+    assert_eq!(rb.pop(), Some((5, 0)));
+    assert!(rb.contains(&(5, 0)));
+    assert_eq!(rb.pop(), Some((5, 0)));
+    assert_eq!(rb.pop(), Some((5, 0)));
+    assert!(!rb.contains(&(5, 0)));
+    assert!(rb.is_empty());
+}
+
+#[test]
+fn len_and_capacity() {
+    let mut rb: RingBuffer<u64> = RingBuffer::with_capacity(3);
+    assert_eq!(rb.len(), 0);
+    assert_eq!(rb.capacity(), 3);
+    rb.extend(0..5);
+    assert_eq!(rb.len(), 3);
+    assert_eq!(rb.capacity(), 3);
+    assert!(rb.is_full());
+}
+
+#[test]
+fn iter_and_index() {
+    let mut rb: RingBuffer<u64> = RingBuffer::with_capacity(4);
+    rb.extend(0..6);
+    assert_eq!(rb.iter().copied().collect::<Vec<u64>>(), vec![2, 3, 4, 5]);
+    assert_eq!((&rb).into_iter().count(), 4);
+    assert_eq!(rb[0], 2);
+    assert_eq!(rb[3], 5);
+    assert_eq!(rb.into_iter().collect::<Vec<u64>>(), vec![2, 3, 4, 5]);
+}
+
+#[test]
+#[should_panic(expected = "Out of bounds")]
+fn index_out_of_bounds() {
+    let rb: RingBuffer<u64> = (0..2).collect();
+    let _ = rb[2];
+}
+
+#[test]
+fn peek() {
+    let mut rb: RingBuffer<u64> = RingBuffer::with_capacity(2);
+    assert_eq!(rb.peek(), None);
+    assert_eq!(rb.peek_back(), None);
+    rb.extend([7, 8, 9]);
+    assert_eq!(rb.peek(), Some(&8));
+    assert_eq!(rb.peek_back(), Some(&9));
+    assert_eq!(rb.len(), 2);
+}
+
+#[test]
+fn clear_and_drain() {
+    let mut rb: RingBuffer<u64> = RingBuffer::with_capacity(4);
+    rb.extend(0..4);
+    assert_eq!(rb.drain().collect::<Vec<u64>>(), vec![0, 1, 2, 3]);
+    assert!(rb.is_empty());
+    assert_eq!(rb.capacity(), 4);
+
+    rb.extend(0..3);
+    rb.clear();
+    assert!(rb.is_empty());
+    assert_eq!(rb.insert(9), None);
+    assert_eq!(rb.peek(), Some(&9));
+}
+
+#[test]
+fn from_iter() {
+    let mut rb: RingBuffer<u64> = (0..3).collect();
+    assert_eq!(rb.capacity(), 3);
+    assert!(rb.is_full());
+    assert_eq!(rb.insert(3), Some(0));
+    assert_eq!(rb.iter().copied().collect::<Vec<u64>>(), vec![1, 2, 3]);
+}
+
+#[test]
+fn zero_capacity() {
+    let mut rb: RingBuffer<u64> = RingBuffer::with_capacity(0);
+    assert!(rb.is_empty());
+    assert!(rb.is_full());
+    assert_eq!(rb.insert(1), Some(1));
+    assert_eq!(rb.insert(2), Some(2));
+    assert!(rb.is_empty());
+    assert_eq!(rb.len(), 0);
+    assert!(!rb.contains(&1));
+    assert_eq!(rb.pop(), None);
+
+    let mut hashed: HashedRingBuffer<u64> = HashedRingBuffer::with_capacity(0);
+    assert_eq!(hashed.insert(1), Some(1));
+    assert!(!hashed.contains(&1));
+    assert!(hashed.is_empty());
+}
+
+#[test]
+fn capacity_one() {
+    let mut rb: RingBuffer<u64> = RingBuffer::with_capacity(1);
+    assert_eq!(rb.insert(1), None);
+    assert_eq!(rb.insert(2), Some(1));
+    assert_eq!(rb.len(), 1);
+    assert_eq!(rb.pop(), Some(2));
+}
+
+#[test]
+fn preallocation() {
+    let mut rb: RingBuffer<u64> = RingBuffer::with_preallocation(1 << 20, 0x800);
+    assert_eq!(rb.capacity(), 1 << 20);
+    rb.extend(0..0x1000);
+    assert_eq!(rb.len(), 0x1000);
+
+    let mut small: RingBuffer<u64> = RingBuffer::with_preallocation(2, 100);
+    small.extend(0..3);
+    assert_eq!(small.iter().copied().collect::<Vec<u64>>(), vec![1, 2]);
+}
+
+#[test]
+fn set_capacity() {
+    let mut rb: RingBuffer<u64> = RingBuffer::with_capacity(5);
+    rb.extend(0..5);
+    assert!(rb.set_capacity(8).is_empty());
+    assert!(!rb.is_full());
+    rb.extend(5..8);
+    assert!(rb.is_full());
+
+    assert_eq!(rb.set_capacity(3), vec![0, 1, 2, 3, 4]);
+    assert_eq!(rb.iter().copied().collect::<Vec<u64>>(), vec![5, 6, 7]);
+    assert_eq!(rb.insert(8), Some(5));
+
+    assert_eq!(rb.set_capacity(0), vec![6, 7, 8]);
+    assert!(rb.is_empty());
+    assert_eq!(rb.insert(9), Some(9));
+}
+
+#[test]
+fn expiring_by_age() {
+    let clock = TestClock::new();
+    let mut set: ExpiringSet<(u8, u64), TestClock> = ExpiringSet::with_clock(Duration::from_secs(10), 100, clock.clone());
+    assert!(set.insert((1, 0)));
+    clock.advance(Duration::from_secs(4));
+    assert!(set.insert((1, 1)));
+    assert!(!set.insert((1, 0)));
+    assert_eq!(set.len(), 2);
+
+    clock.advance(Duration::from_secs(6));
+    assert!(!set.contains(&(1, 0)));
+    assert!(set.contains(&(1, 1)));
+    assert_eq!(set.len(), 1);
+
+    // expired elements can be inserted again
+    assert!(set.insert((1, 0)));
+    clock.advance(Duration::from_secs(10));
+    assert!(set.is_empty());
+    set.expire();
+    assert!(!set.contains(&(1, 0)));
+}
+
+#[test]
+fn expiring_by_count() {
+    let clock = TestClock::new();
+    let mut set: ExpiringSet<u64, TestClock> = ExpiringSet::with_clock(Duration::from_secs(10), 3, clock.clone());
+    for i in 0..5 {
+        assert!(set.insert(i));
+    }
+    assert_eq!(set.len(), 3);
+    assert!(!set.contains(&1));
+    assert!(set.contains(&2));
+    assert!(set.contains(&4));
+
+    set.clear();
+    assert!(set.is_empty());
+
+    let mut none: ExpiringSet<u64> = ExpiringSet::new(Duration::from_secs(10), 0);
+    assert!(!none.insert(1));
+    assert!(!none.contains(&1));
+}
+
+#[test]
+fn concurrent_single_thread() {
+    let rb: ConcurrentRingBuffer<u64> = ConcurrentRingBuffer::with_capacity(3);
+    assert!(rb.is_empty());
+    assert_eq!(rb.pop(), None);
+    for i in 0..3 {
+        assert_eq!(rb.insert(i), None);
+    }
+    assert_eq!(rb.len(), 3);
+    assert_eq!(rb.insert(3), Some(0));
+    assert_eq!(rb.insert(4), Some(1));
+    assert_eq!(rb.pop(), Some(2));
+    assert_eq!(rb.pop(), Some(3));
+    assert_eq!(rb.pop(), Some(4));
+    assert_eq!(rb.pop(), None);
+
+    let one: ConcurrentRingBuffer<u64> = ConcurrentRingBuffer::with_capacity(1);
+    assert_eq!(one.insert(1), None);
+    assert_eq!(one.insert(2), Some(1));
+    assert_eq!(one.pop(), Some(2));
+
+    let zero: ConcurrentRingBuffer<u64> = ConcurrentRingBuffer::with_capacity(0);
+    assert_eq!(zero.insert(1), Some(1));
+    assert_eq!(zero.pop(), None);
+}
+
+#[test]
+fn concurrent_drop() {
+    let counter = Arc::new(());
+    let rb = ConcurrentRingBuffer::with_capacity(4);
+    for _ in 0..6 {
+        rb.insert(Arc::clone(&counter));
+    }
+    assert_eq!(Arc::strong_count(&counter), 5);
+    drop(rb);
+    assert_eq!(Arc::strong_count(&counter), 1);
+}
+
+/// every element must be removed or evicted exactly once,
+/// and the elements of a producer must be removed in insertion order
+fn stress(producers: u64, consumers: usize, capacity: usize) {
+    const PER_PRODUCER: u64 = 20_000;
+    let rb = Arc::new(ConcurrentRingBuffer::with_capacity(capacity));
+    let done = Arc::new(AtomicBool::new(false));
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let consumer_handles: Vec<_> = (0..consumers)
+        .map(|_| {
+            let (rb, done, seen) = (Arc::clone(&rb), Arc::clone(&done), Arc::clone(&seen));
+            thread::spawn(move || {
+                let mut last = vec![None; usize::try_from(producers).unwrap()];
+                let mut local = Vec::new();
+                loop {
+                    let finished = done.load(Ordering::Acquire);
+                    match rb.pop() {
+                        Some((p, i)) => {
+                            let p = usize::try_from(p).unwrap();
+                            assert!(last[p].is_none_or(|l| l < i), "out of order");
+                            last[p] = Some(i);
+                            local.push((p, i));
+                        }
+                        None if finished => break,
+                        None => thread::yield_now(),
+                    }
+                }
+                seen.lock().unwrap().extend(local);
+            })
+        })
+        .collect();
+
+    let producer_handles: Vec<_> = (0..producers)
+        .map(|p| {
+            let rb = Arc::clone(&rb);
+            thread::spawn(move || {
+                let mut evicted = Vec::new();
+                for i in 0..PER_PRODUCER {
+                    evicted.extend(rb.insert((p, i)));
+                }
+                evicted
+            })
+        })
+        .collect();
+
+    let mut evicted = 0;
+    for h in producer_handles {
+        evicted += h.join().unwrap().len();
+    }
+    done.store(true, Ordering::Release);
+    for h in consumer_handles {
+        h.join().unwrap();
+    }
+
+    let mut seen = seen.lock().unwrap();
+    let len = seen.len();
+    seen.sort_unstable();
+    seen.dedup();
+    assert_eq!(seen.len(), len, "element removed twice");
+    let total = usize::try_from(producers * PER_PRODUCER).unwrap();
+    assert_eq!(len + evicted, total);
+    assert!(rb.is_empty());
+}
+
+#[test]
+fn concurrent_spsc_stress() {
+    stress(1, 1, 8);
+}
+
+#[test]
+fn concurrent_mpmc_stress() {
+    stress(4, 4, 16);
+}
+
+#[test]
+fn concurrent_overwrite_stress() {
+    stress(4, 1, 1);
+}
+
+#[test]
+fn concurrent_insert_waits_paused_pop() {
+    let rb = Arc::new(ConcurrentRingBuffer::with_capacity(4));
+    for i in 0..4 {
+        rb.insert(i);
+    }
+    // a consumer removed the head but has not released its slot yet
+    let claimed = rb.claim().unwrap();
+
+    let producer = {
+        let rb = Arc::clone(&rb);
+        thread::spawn(move || rb.insert(4))
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert!(!producer.is_finished());
+    assert_eq!(rb.len(), 3);
+
+    assert_eq!(claimed.take(), 0);
+    assert_eq!(producer.join().unwrap(), None);
+    assert_eq!(rb.insert(5), Some(1));
+    let rest: Vec<_> = std::iter::from_fn(|| rb.pop()).collect();
+    assert_eq!(rest, vec![2, 3, 4, 5]);
+}
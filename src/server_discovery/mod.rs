@@ -0,0 +1,125 @@
+/*!
+    This module contains the coordinator used by clients to discover the type of every server in the network
+*/
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use wg_2024::network::NodeId;
+
+use crate::networking::topology::Topology;
+use crate::slc_commands::ServerType;
+use crate::web_messages::{Compression, GenericResponse, RequestMessage, Response, ResponseMessage};
+
+/// Collects the answers to the `Request::Type` sent to every server
+///
+/// servers that do not answer before the timeout are left out of the resulting map,
+/// which is meant to be sent to scl in a `ServersTypes` event
+#[derive(Debug, Clone)]
+pub struct TypeDiscovery {
+    started: Instant,
+    timeout: Duration,
+    pending: HashSet<NodeId>,
+    types: HashMap<NodeId, ServerType>,
+}
+
+impl TypeDiscovery {
+    /// Constructor of a discovery started now
+    /// * servers: IDs of the servers to be queried
+    /// * timeout: time after which silent servers are ignored
+    #[must_use]
+    pub fn new(servers: impl IntoIterator<Item = NodeId>, timeout: Duration) -> Self {
+        Self {
+            started: Instant::now(),
+            timeout,
+            pending: servers.into_iter().collect(),
+            types: HashMap::new(),
+        }
+    }
+
+    /// Constructor of a discovery querying every server found by flooding
+    /// * topology: graph of the network
+    /// * timeout: time after which silent servers are ignored
+    #[must_use]
+    pub fn from_topology(topology: &Topology, timeout: Duration) -> Self {
+        Self::new(topology.servers(), timeout)
+    }
+
+    /// produces a `Request::Type` for every server that has not answered yet
+    ///
+    /// returns pairs (server ID, request)
+    /// * `source_id`: ID of the client
+    /// * `compression_type`: compression of the requests
+    #[must_use]
+    pub fn requests(&self, source_id: NodeId, compression_type: &Compression) -> Vec<(NodeId, RequestMessage)> {
+        let mut pending: Vec<NodeId> = self.pending.iter().copied().collect();
+        pending.sort_unstable();
+        pending
+            .into_iter()
+            .map(|id| (id, RequestMessage::new_type_request(source_id, compression_type.clone())))
+            .collect()
+    }
+
+    /// records the type of a server
+    ///
+    /// returns false if the server was not being queried
+    /// * `server_id`: ID of the server
+    /// * `server_type`: type of the server
+    pub fn add_type(&mut self, server_id: NodeId, server_type: ServerType) -> bool {
+        if self.pending.remove(&server_id) {
+            self.types.insert(server_id, server_type);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// records the type carried by a `GenericResponse::Type` or `GenericResponse::Capabilities`
+    ///
+    /// returns false if the response does not carry a type or the server was not being queried
+    /// * response: response received from a server
+    pub fn handle_response(&mut self, response: &ResponseMessage) -> bool {
+        match response.content {
+            Response::Generic(GenericResponse::Type(t) | GenericResponse::Capabilities(t, _)) => {
+                self.add_type(response.source_id, t)
+            }
+            _ => false,
+        }
+    }
+
+    /// retrieves the IDs of the servers that have not answered yet
+    #[must_use]
+    pub fn pending(&self) -> HashSet<NodeId> {
+        self.pending.clone()
+    }
+
+    /// checks if the timeout is over
+    #[inline]
+    #[must_use]
+    pub fn is_timed_out(&self) -> bool {
+        self.started.elapsed() >= self.timeout
+    }
+
+    /// checks if every server answered or the timeout is over
+    #[inline]
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty() || self.is_timed_out()
+    }
+
+    /// Getter of the types collected so far
+    #[inline]
+    #[must_use]
+    pub fn types(&self) -> &HashMap<NodeId, ServerType> {
+        &self.types
+    }
+
+    /// returns the completed map, or None while some servers may still answer
+    #[must_use]
+    pub fn finish(self) -> Option<HashMap<NodeId, ServerType>> {
+        self.is_complete().then_some(self.types)
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use wg_2024::network::NodeId;
+
+use crate::server_discovery::TypeDiscovery;
+use crate::slc_commands::ServerType;
+use crate::web_messages::{Compression, Request, ResponseMessage};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[test]
+fn discovery() {
+    let mut d = TypeDiscovery::new([20, 21, 22], TIMEOUT);
+    let requests = d.requests(1, &Compression::None);
+    assert_eq!(requests.iter().map(|(id, _)| *id).collect::<Vec<NodeId>>(), vec![20, 21, 22]);
+    assert!(requests.iter().all(|(_, r)| r.source_id == 1 && matches!(r.content, Request::Type)));
+
+    assert!(d.handle_response(&ResponseMessage::new_type_response(20, Compression::None, ServerType::ChatServer)));
+    assert!(d.handle_response(&ResponseMessage::new_capabilities_response(
+        21,
+        Compression::None,
+        ServerType::MediaServer,
+        vec![Compression::None]
+    )));
+    assert!(!d.handle_response(&ResponseMessage::new_type_response(30, Compression::None, ServerType::FileServer)));
+    assert!(!d.handle_response(&ResponseMessage::new_media_list_response(22, Compression::None, vec![])));
+    assert_eq!(d.pending(), HashSet::from([22]));
+    assert!(!d.is_complete());
+    assert!(d.clone().finish().is_none());
+
+    assert!(d.add_type(22, ServerType::FileServer));
+    assert!(!d.add_type(22, ServerType::ChatServer));
+    let types = d.finish().unwrap();
+    assert_eq!(types.len(), 3);
+    assert_eq!(types[&22], ServerType::FileServer);
+}
+
+#[test]
+fn timeout() {
+    let mut d = TypeDiscovery::new([20, 21], Duration::ZERO);
+    assert!(d.add_type(21, ServerType::FileServer));
+    assert!(d.is_timed_out());
+    assert!(d.is_complete());
+    let types = d.finish().unwrap();
+    assert_eq!(types.keys().copied().collect::<Vec<NodeId>>(), vec![21]);
+}
@@ -8,6 +8,10 @@ use crossbeam_channel::Sender;
 use std::collections::HashMap;
 use wg_2024::{network::NodeId, packet::Packet};
 
+use crate::chat_messages::ChatError;
+use crate::checksum::VerifiedFile;
+use crate::web_messages::ErrorCode;
+
 /// Contains both a text file and the linked media files
 /// Web client sends this message to the scl when all the necessary files have been retrieved from the network
 #[derive(Debug, Clone, PartialEq)]
@@ -20,14 +24,14 @@ pub struct TextMediaResponse {
 
 impl TextMediaResponse {
     /// Constructor for `TextMediaResponse`
-    /// * `html_file`: pair (text filename, text file content)
-    /// * `media_files`: array of pairs (media filename, media file content)
+    /// * `html_file`: verified text file
+    /// * `media_files`: array of verified media files
     #[inline]
     #[must_use]
-    pub fn new(html_file: (String, Vec<u8>), media_files: Vec<(String, Vec<u8>)>) -> Self {
+    pub fn new(html_file: VerifiedFile, media_files: Vec<VerifiedFile>) -> Self {
         Self {
-            html_file,
-            media_files,
+            html_file: html_file.into_parts(),
+            media_files: media_files.into_iter().map(VerifiedFile::into_parts).collect(),
         }
     }
 
@@ -85,6 +89,36 @@ pub enum WebClientEvent {
     FileFromClient(TextMediaResponse, NodeId), // file content (first vec is html, others are media), server_id (maybe client_id)
     /// inform that client received an unsupported request
     UnsupportedRequest,
+    /// inform scl that the server identified by the given ID rejected a request as invalid
+    InvalidRequest(NodeId, Option<String>), // server_id, message
+    /// inform scl that the server identified by the given ID does not have the requested file
+    FileNotFound(NodeId, Option<String>), // server_id, message
+    /// inform scl that the server identified by the given ID is overloaded
+    ServerOverloaded(NodeId, Option<String>), // server_id, message
+    /// inform scl that the server identified by the given ID does not support the requested compression
+    UnsupportedCompression(NodeId, Option<String>), // server_id, message
+    /// inform scl that a request has been sent to a server of the wrong type
+    WrongServerType(NodeId, Option<String>), // server_id, message
+    /// inform scl that the server identified by the given ID failed while serving a request
+    ServerInternalError(NodeId, Option<String>), // server_id, message
+}
+
+impl WebClientEvent {
+    /// builds the event that reports an error response to scl
+    /// * `server_id`: ID of the server that sent the error
+    /// * code: error code of the response
+    /// * message: optional message of the response
+    #[must_use]
+    pub fn from_error(server_id: NodeId, code: ErrorCode, message: Option<String>) -> Self {
+        match code {
+            ErrorCode::InvalidRequest => WebClientEvent::InvalidRequest(server_id, message),
+            ErrorCode::NotFound => WebClientEvent::FileNotFound(server_id, message),
+            ErrorCode::Overloaded => WebClientEvent::ServerOverloaded(server_id, message),
+            ErrorCode::UnsupportedCompression => WebClientEvent::UnsupportedCompression(server_id, message),
+            ErrorCode::WrongServerType => WebClientEvent::WrongServerType(server_id, message),
+            ErrorCode::Internal => WebClientEvent::ServerInternalError(server_id, message),
+        }
+    }
 }
 
 impl ClientEvent for WebClientEvent {}
@@ -99,9 +133,21 @@ pub enum ChatClientCommand {
     /// Ask a client to discover the server types in the network
     AskServersTypes,
     /// Ask a client to connect to the server identified by the given ID
-    //ConnectToChatServer(NodeId), // chat_server_id
+    ConnectToChatServer(NodeId), // chat_server_id
+    /// Ask a client to retrieve the clients connected to the chat server with the given ID
+    AskListOfClients(NodeId), // chat_server_id
     /// Ask a client to send a message (first parameter) to anothe client (second parameter) through a chat server (third parameter)
-    //SendChatText(String, NodeId, NodeId), // text, client_id, chat_server_id
+    SendChatText(String, NodeId, NodeId), // text, client_id, chat_server_id
+    /// Ask a client to create a room with the given name on the chat server with the given ID
+    CreateRoom(String, NodeId), // room, chat_server_id
+    /// Ask a client to join a room of the chat server with the given ID
+    JoinRoom(String, NodeId), // room, chat_server_id
+    /// Ask a client to leave a room of the chat server with the given ID
+    LeaveRoom(String, NodeId), // room, chat_server_id
+    /// Ask a client to retrieve the members of a room of the chat server with the given ID
+    AskRoomMembers(String, NodeId), // room, chat_server_id
+    /// Ask a client to send a message (first parameter) to every member of a room (second parameter) through a chat server (third parameter)
+    SendRoomText(String, String, NodeId), // text, room, chat_server_id
     /// Scl shortcuts a packet to the client
     Shortcut(Packet),
     /// Send a text message for processing by the client
@@ -119,10 +165,33 @@ pub enum ChatClientEvent {
     Shortcut(Packet),
     /// communicate to scl the servers type of the servers in the network
     ServersTypes(HashMap<NodeId, ServerType>), // server_id, server_type
+    /// communicate to scl that the client is now registered to the chat server with the given ID
+    ConnectedToChatServer(NodeId), // chat_server_id
     /// communicate to scl all the clients connected to a chat server
-    //ClientsConnectedToChatServer(NodeId, Vec<NodeId>),
+    ClientsConnectedToChatServer(NodeId, Vec<NodeId>), // chat_server_id, client_ids
     /// communicate to scl that a new message has arrived from the node with the given ID
-    //NewMessageFrom(NodeId), // client_id, maybe add also chat_server_id
+    NewMessageFrom(NodeId, NodeId, String), // client_id, chat_server_id, text
+    /// communicate to scl that a message sent with `SendChatText` reached its recipient
+    MessageDelivered(NodeId, NodeId), // client_id, chat_server_id
+    /// communicate to scl that the recipient of a message sent with `SendChatText` is not reachable
+    /// and the chat server queued the message
+    MessageQueued(NodeId, NodeId), // client_id, chat_server_id
+    /// communicate to scl that a message sent with `SendChatText` could not reach the chat server
+    MessageDeliveryFailed(NodeId, NodeId), // client_id, chat_server_id
+    /// communicate to scl that the chat server with the given ID answered with an error
+    ChatServerError(NodeId, ChatError), // chat_server_id, error
+    /// communicate to scl that the client created a room (and joined it)
+    RoomCreated(String, NodeId), // room, chat_server_id
+    /// communicate to scl that the client joined a room
+    RoomJoined(String, NodeId), // room, chat_server_id
+    /// communicate to scl that the client left a room
+    RoomLeft(String, NodeId), // room, chat_server_id
+    /// communicate to scl the members of a room
+    RoomMembers(String, NodeId, Vec<NodeId>), // room, chat_server_id, client_ids
+    /// communicate to scl that a message sent with `SendRoomText` has been broadcast to the room
+    RoomMessageSent(String, NodeId), // room, chat_server_id
+    /// communicate to scl that a new message has arrived in a room from the node with the given ID
+    NewRoomMessageFrom(NodeId, String, NodeId, String), // client_id, room, chat_server_id, text
     /// inform that client received an unsupported request
     UnsupportedRequest,
     /// Receive text to print out
@@ -1,11 +1,19 @@
 /*! This module contains the types used to implement communication between web clients and web servers */
 
+#[cfg(test)]
+mod tests;
+
 use core::fmt;
 use bincode::config::{standard, Configuration};
+use bincode::de::Decoder;
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
 use bincode::{Encode, Decode};
 use serde::{Serialize, de::DeserializeOwned};
 
 use wg_2024::network::NodeId;
+use crate::checksum::{crc32, IntegrityError, VerifiedFile};
+use crate::compression::CompressionError;
 use crate::ServerType;
 
 /// Compression type to be used in a web client-server communication
@@ -87,6 +95,10 @@ impl WebMessage for TextRequest {}
 pub enum MediaRequest {
     MediaList,
     Media(String),
+    /// size and checksum of a media file
+    MediaInfo(String),
+    /// part of a media file
+    MediaRange(String, u64, u64), // file, offset, length
 }
 impl WebMessage for MediaRequest {}
 
@@ -101,14 +113,51 @@ impl WebMessage for TextResponse {}
 pub enum MediaResponse {
     MediaList(Vec<String>),
     Media(Vec<u8>),
+    MediaInfo(u64, u32), // size, crc32
+    /// part of a media file, shorter than requested if the file ends before
+    MediaRange(u64, Vec<u8>), // offset, data
 }
 impl WebMessage for MediaResponse {}
 
+/// Machine readable reason of a `GenericResponse::Error`
+#[derive(Debug, Clone, Copy, Encode, Decode, Hash, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// the request cannot be parsed or has invalid parameters
+    InvalidRequest,
+    /// the requested file does not exist
+    NotFound,
+    /// the server is too busy to serve the request, it can be sent again later
+    Overloaded,
+    /// the server does not support the compression of the request
+    UnsupportedCompression,
+    /// the request cannot be served by this type of server
+    WrongServerType,
+    /// the server failed while serving the request
+    Internal,
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCode::InvalidRequest => write!(f, "Invalid request"),
+            ErrorCode::NotFound => write!(f, "Not found"),
+            ErrorCode::Overloaded => write!(f, "Server overloaded"),
+            ErrorCode::UnsupportedCompression => write!(f, "Unsupported compression"),
+            ErrorCode::WrongServerType => write!(f, "Wrong server type"),
+            ErrorCode::Internal => write!(f, "Internal server error"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
 pub enum GenericResponse {
     Type(ServerType),
     InvalidRequest,
     NotFound,
+    /// server type and compressions supported by the server
+    Capabilities(ServerType, Vec<Compression>),
+    /// request that could not be served, with an optional human readable message
+    Error(ErrorCode, Option<String>), // code, message
 }
 
 #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
@@ -116,6 +165,7 @@ pub enum Request {
     Media(MediaRequest),
     Text(TextRequest),
     Type,
+    Capabilities,
 }
 
 #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
@@ -132,13 +182,81 @@ pub struct RequestMessage {
     pub content: Request,
 }
 
-#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+/// Response sent by a web server
+///
+/// The body of `TextResponse::Text`, `MediaResponse::Media` and `MediaResponse::MediaRange` is compressed with `compression_type`
+/// when the message is serialized and decompressed when it is deserialized
+///
+/// `digest` is the crc32 of the uncompressed body, set by the constructors of responses carrying a file
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResponseMessage {
     pub source_id: NodeId,
     pub compression_type: Compression,
     pub content: Response,
+    pub digest: Option<u32>,
 }
 
+impl Response {
+    /// retrieves the file content carried by the response, if any
+    fn body(&self) -> Option<&[u8]> {
+        match self {
+            Response::Text(TextResponse::Text(data))
+            | Response::Media(MediaResponse::Media(data) | MediaResponse::MediaRange(_, data)) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// applies f to the file content carried by the response, if any
+    fn map_body<F>(self, f: F) -> Result<Self, CompressionError>
+    where
+        F: FnOnce(Vec<u8>) -> Result<Vec<u8>, CompressionError>,
+    {
+        Ok(match self {
+            Response::Text(TextResponse::Text(data)) => Response::Text(TextResponse::Text(f(data)?)),
+            Response::Media(MediaResponse::Media(data)) => Response::Media(MediaResponse::Media(f(data)?)),
+            Response::Media(MediaResponse::MediaRange(offset, data)) => {
+                Response::Media(MediaResponse::MediaRange(offset, f(data)?))
+            }
+            other => other,
+        })
+    }
+}
+
+impl Encode for ResponseMessage {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.source_id.encode(encoder)?;
+        self.compression_type.encode(encoder)?;
+        if self.compression_type == Compression::None {
+            self.content.encode(encoder)?;
+        } else {
+            self.content
+                .clone()
+                .map_body(|data| Ok(self.compression_type.compress(&data)))
+                .map_err(|_| EncodeError::Other("cannot compress response body"))?
+                .encode(encoder)?;
+        }
+        self.digest.encode(encoder)
+    }
+}
+
+impl<Context> Decode<Context> for ResponseMessage {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let source_id = NodeId::decode(decoder)?;
+        let compression_type = Compression::decode(decoder)?;
+        let content = Response::decode(decoder)?
+            .map_body(|data| compression_type.decompress(&data))
+            .map_err(|_| DecodeError::Other("cannot decompress response body"))?;
+        let digest = Option::<u32>::decode(decoder)?;
+        Ok(Self {
+            source_id,
+            compression_type,
+            content,
+            digest,
+        })
+    }
+}
+bincode::impl_borrow_decode!(ResponseMessage);
+
 impl RequestMessage {
     #[inline]
     #[must_use]
@@ -180,6 +298,26 @@ impl RequestMessage {
         }
     }
 
+    #[inline]
+    #[must_use]
+    pub fn new_media_info_request(source_id: NodeId, compression_type: Compression, file: String) -> RequestMessage {
+        Self {
+            source_id,
+            compression_type,
+            content: Request::Media(MediaRequest::MediaInfo(file)),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_media_range_request(source_id: NodeId, compression_type: Compression, file: String, offset: u64, length: u64) -> RequestMessage {
+        Self {
+            source_id,
+            compression_type,
+            content: Request::Media(MediaRequest::MediaRange(file, offset, length)),
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn new_type_request(source_id: NodeId, compression_type: Compression) -> RequestMessage {
@@ -189,9 +327,61 @@ impl RequestMessage {
             content: Request::Type,
         }
     }
+
+    #[inline]
+    #[must_use]
+    pub fn new_capabilities_request(source_id: NodeId, compression_type: Compression) -> RequestMessage {
+        Self {
+            source_id,
+            compression_type,
+            content: Request::Capabilities,
+        }
+    }
 }
 
 impl ResponseMessage {
+    /// retrieves the error carried by the response, if any
+    ///
+    /// `GenericResponse::InvalidRequest` and `GenericResponse::NotFound` are mapped to their error code
+    #[must_use]
+    pub fn error(&self) -> Option<(ErrorCode, Option<&str>)> {
+        match &self.content {
+            Response::Generic(GenericResponse::InvalidRequest) => Some((ErrorCode::InvalidRequest, None)),
+            Response::Generic(GenericResponse::NotFound) => Some((ErrorCode::NotFound, None)),
+            Response::Generic(GenericResponse::Error(code, message)) => Some((*code, message.as_deref())),
+            _ => None,
+        }
+    }
+
+    /// checks the file carried by the response against its digest
+    ///
+    /// responses that do not carry a file are always valid
+    /// # Errors
+    ///
+    /// Will return Err if the digest is missing or does not match
+    pub fn verify(&self) -> Result<(), IntegrityError> {
+        match (self.content.body(), self.digest) {
+            (None, _) => Ok(()),
+            (Some(_), None) => Err(IntegrityError::MissingDigest),
+            (Some(data), Some(digest)) => crate::checksum::verify(data, digest),
+        }
+    }
+
+    /// verifies the file carried by a `TextResponse::Text` or `MediaResponse::Media` response
+    /// * name: filename that has been requested
+    /// # Errors
+    ///
+    /// Will return Err if the response does not carry a whole file or if it does not match its digest
+    pub fn into_verified_file(self, name: String) -> Result<VerifiedFile, IntegrityError> {
+        let digest = self.digest;
+        match self.content {
+            Response::Text(TextResponse::Text(data)) | Response::Media(MediaResponse::Media(data)) => {
+                VerifiedFile::new(name, data, digest.ok_or(IntegrityError::MissingDigest)?)
+            }
+            _ => Err(IntegrityError::NotAFile),
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn new_type_response(source_id: NodeId, compression_type: Compression, server_type: ServerType) -> ResponseMessage {
@@ -199,6 +389,18 @@ impl ResponseMessage {
             source_id,
             compression_type,
             content: Response::Generic(GenericResponse::Type(server_type)),
+            digest: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_capabilities_response(source_id: NodeId, compression_type: Compression, server_type: ServerType, compressions: Vec<Compression>) -> ResponseMessage {
+        Self {
+            source_id,
+            compression_type,
+            content: Response::Generic(GenericResponse::Capabilities(server_type, compressions)),
+            digest: None,
         }
     }
 
@@ -209,6 +411,7 @@ impl ResponseMessage {
             source_id,
             compression_type,
             content: Response::Generic(GenericResponse::NotFound),
+            digest: None,
         }
     }
 
@@ -219,9 +422,45 @@ impl ResponseMessage {
             source_id,
             compression_type,
             content: Response::Generic(GenericResponse::InvalidRequest),
+            digest: None,
         }
     }
 
+    #[inline]
+    #[must_use]
+    pub fn new_error_response(source_id: NodeId, compression_type: Compression, code: ErrorCode, message: Option<String>) -> ResponseMessage {
+        Self {
+            source_id,
+            compression_type,
+            content: Response::Generic(GenericResponse::Error(code, message)),
+            digest: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_overloaded_response(source_id: NodeId, compression_type: Compression, message: Option<String>) -> ResponseMessage {
+        Self::new_error_response(source_id, compression_type, ErrorCode::Overloaded, message)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_unsupported_compression_response(source_id: NodeId, compression_type: Compression, message: Option<String>) -> ResponseMessage {
+        Self::new_error_response(source_id, compression_type, ErrorCode::UnsupportedCompression, message)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_wrong_server_type_response(source_id: NodeId, compression_type: Compression, message: Option<String>) -> ResponseMessage {
+        Self::new_error_response(source_id, compression_type, ErrorCode::WrongServerType, message)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_internal_error_response(source_id: NodeId, compression_type: Compression, message: Option<String>) -> ResponseMessage {
+        Self::new_error_response(source_id, compression_type, ErrorCode::Internal, message)
+    }
+
     #[inline]
     #[must_use]
     pub fn new_text_list_response(source_id: NodeId, compression_type: Compression, list: Vec<String>) -> ResponseMessage {
@@ -229,16 +468,19 @@ impl ResponseMessage {
             source_id,
             compression_type,
             content: Response::Text(TextResponse::TextList(list)),
+            digest: None,
         }
     }
 
     #[inline]
     #[must_use]
     pub fn new_text_response(source_id: NodeId, compression_type: Compression, data: Vec<u8>) -> ResponseMessage {
+        let digest = Some(crc32(&data));
         Self {
             source_id,
             compression_type,
             content: Response::Text(TextResponse::Text(data)),
+            digest,
         }
     }
 
@@ -249,16 +491,42 @@ impl ResponseMessage {
             source_id,
             compression_type,
             content: Response::Media(MediaResponse::MediaList(list)),
+            digest: None,
         }
     }
 
     #[inline]
     #[must_use]
     pub fn new_media_response(source_id: NodeId, compression_type: Compression, data: Vec<u8>) -> ResponseMessage {
+        let digest = Some(crc32(&data));
         Self {
             source_id,
             compression_type,
             content: Response::Media(MediaResponse::Media(data)),
+            digest,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_media_info_response(source_id: NodeId, compression_type: Compression, size: u64, checksum: u32) -> ResponseMessage {
+        Self {
+            source_id,
+            compression_type,
+            content: Response::Media(MediaResponse::MediaInfo(size, checksum)),
+            digest: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn new_media_range_response(source_id: NodeId, compression_type: Compression, offset: u64, data: Vec<u8>) -> ResponseMessage {
+        let digest = Some(crc32(&data));
+        Self {
+            source_id,
+            compression_type,
+            content: Response::Media(MediaResponse::MediaRange(offset, data)),
+            digest,
         }
     }
 }
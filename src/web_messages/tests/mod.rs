@@ -0,0 +1,63 @@
+use bincode::config::standard;
+
+use crate::slc_commands::{ServerType, WebClientEvent};
+use crate::web_messages::{
+    Compression, ErrorCode, GenericResponse, Request, RequestMessage, Response, ResponseMessage, Serializable,
+};
+
+#[test]
+fn capabilities_round_trip() {
+    let req = RequestMessage::new_capabilities_request(1, Compression::None);
+    let decoded = <RequestMessage as Serializable>::deserialize(req.serialize().unwrap()).unwrap();
+    assert_eq!(decoded, req);
+    assert!(matches!(decoded.content, Request::Capabilities));
+
+    let compressions = vec![Compression::LZW, Compression::Huffman, Compression::None];
+    let resp = ResponseMessage::new_capabilities_response(2, Compression::None, ServerType::MediaServer, compressions.clone());
+    let decoded = <ResponseMessage as Serializable>::deserialize(resp.serialize().unwrap()).unwrap();
+    assert_eq!(decoded, resp);
+    assert_eq!(
+        decoded.content,
+        Response::Generic(GenericResponse::Capabilities(ServerType::MediaServer, compressions))
+    );
+}
+
+#[test]
+fn generic_response_variant_numbers() {
+    // variants added after the first release must not renumber the existing ones
+    let encode = |r: GenericResponse| bincode::encode_to_vec(r, standard()).unwrap()[0];
+    assert_eq!(encode(GenericResponse::Type(ServerType::ChatServer)), 0);
+    assert_eq!(encode(GenericResponse::InvalidRequest), 1);
+    assert_eq!(encode(GenericResponse::NotFound), 2);
+}
+
+#[test]
+fn error_responses() {
+    let msg = ResponseMessage::new_overloaded_response(3, Compression::Huffman, Some("retry later".to_string()));
+    let msg = <ResponseMessage as Serializable>::deserialize(msg.serialize().unwrap()).unwrap();
+    assert_eq!(msg.error(), Some((ErrorCode::Overloaded, Some("retry later"))));
+
+    assert_eq!(
+        ResponseMessage::new_wrong_server_type_response(3, Compression::None, None).error(),
+        Some((ErrorCode::WrongServerType, None))
+    );
+    assert_eq!(
+        ResponseMessage::new_not_found_response(3, Compression::None).error(),
+        Some((ErrorCode::NotFound, None))
+    );
+    assert_eq!(ResponseMessage::new_text_response(3, Compression::None, vec![1]).error(), None);
+}
+
+#[test]
+fn error_events() {
+    let msg = ResponseMessage::new_unsupported_compression_response(3, Compression::None, None);
+    let (code, message) = msg.error().unwrap();
+    assert_eq!(
+        WebClientEvent::from_error(msg.source_id, code, message.map(str::to_string)),
+        WebClientEvent::UnsupportedCompression(3, None)
+    );
+    assert_eq!(
+        WebClientEvent::from_error(4, ErrorCode::Internal, Some("disk".to_string())),
+        WebClientEvent::ServerInternalError(4, Some("disk".to_string()))
+    );
+}